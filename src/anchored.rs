@@ -0,0 +1,86 @@
+use crate::{source, Span, Spanned};
+use bstr::ByteSlice;
+use eyre::{eyre, Result};
+
+/// A span that remembers the text it covered, so it can tell whether the file changed
+/// since, and find the text again if it moved.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnchoredSpan {
+    pub span: Span,
+    pub text: Vec<u8>,
+}
+
+/// Where an `AnchoredSpan` is in the current version of its file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Resolved {
+    /// The text is still at the original position.
+    Unchanged(Span),
+    /// The text was found elsewhere; this is the occurrence closest to the original.
+    Moved(Span),
+    /// The text does not exist in the file anymore. Contains the original span.
+    Stale(Span),
+}
+
+impl AnchoredSpan {
+    /// Read the text covered by the span from its file.
+    /// Fails if the span is out of bounds of the file.
+    pub fn new(span: Span) -> Result<Self> {
+        let source = source::read(&span.file)?;
+        let Some(text) = source.get(span.bytes.clone()) else {
+            return Err(eyre!("{span} is out of bounds"));
+        };
+        Ok(Self {
+            text: text.to_vec(),
+            span,
+        })
+    }
+
+    /// Find the anchored text in the current version of the file. This always reads
+    /// the file again, and updates the cache used by other lookups.
+    pub fn resolve(&self) -> Result<Resolved> {
+        let source = source::reload(&self.span.file)?;
+        Ok(self.resolve_in(&source.content))
+    }
+
+    fn resolve_in(&self, source: &[u8]) -> Resolved {
+        if source.get(self.span.bytes.clone()) == Some(&self.text[..]) {
+            return Resolved::Unchanged(self.span.clone());
+        }
+        if self.text.is_empty() {
+            return Resolved::Stale(self.span.clone());
+        }
+        let start = self.span.bytes.start;
+        match source
+            .find_iter(&self.text)
+            .min_by_key(|&pos| pos.abs_diff(start))
+        {
+            Some(pos) => Resolved::Moved(Span {
+                file: self.span.file,
+                bytes: pos..pos + self.text.len(),
+            }),
+            None => Resolved::Stale(self.span.clone()),
+        }
+    }
+}
+
+impl Resolved {
+    pub fn span(&self) -> &Span {
+        match self {
+            Resolved::Unchanged(span) | Resolved::Moved(span) | Resolved::Stale(span) => span,
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        matches!(self, Resolved::Stale(_))
+    }
+}
+
+impl<T: AsRef<[u8]>> Spanned<T> {
+    /// Anchor the span to the current content, without reading the file.
+    pub fn anchor(&self) -> AnchoredSpan {
+        AnchoredSpan {
+            span: self.span.clone(),
+            text: self.content.as_ref().to_vec(),
+        }
+    }
+}
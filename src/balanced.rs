@@ -0,0 +1,42 @@
+use crate::{err, Spanned};
+use eyre::Result;
+
+impl Spanned<&str> {
+    /// Find the first `open` delimiter and its matching `close`, skipping nested pairs and
+    /// delimiters inside `"..."` strings. Returns the text before the opening delimiter,
+    /// the text between the delimiters and the text after the closing one,
+    /// or `None` if there is no opening delimiter.
+    pub fn split_balanced(&self, open: char, close: char) -> Result<Option<(Self, Self, Self)>> {
+        let Some(start) = self.find(open) else {
+            return Ok(None);
+        };
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut escaped = false;
+        for (i, c) in self[start..].char_indices() {
+            if in_string {
+                in_string = escaped || c != '"';
+                escaped = !escaped && c == '\\';
+            } else if c == '"' {
+                in_string = true;
+            } else if c == open {
+                depth += 1;
+            } else if c == close {
+                depth -= 1;
+                if depth == 0 {
+                    let (before, rest) = self.split_at(start);
+                    let (inside, after) = rest.split_at(i);
+                    let inside = inside.split_at(open.len_utf8()).1;
+                    let after = after.split_at(close.len_utf8()).1;
+                    return Ok(Some((before, inside, after)));
+                }
+            }
+        }
+        let opened = self
+            .span
+            .clone()
+            .inc_col_start(start)
+            .set_col_end_relative_to_start(open.len_utf8());
+        Err(err!(opened, "unclosed delimiter `{open}`"))
+    }
+}
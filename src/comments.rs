@@ -0,0 +1,37 @@
+use crate::Spanned;
+
+impl<'a> Spanned<&'a str> {
+    /// Remove everything from any of the `markers` (e.g. `#` or `//`) to the end of
+    /// the line. Returns the remaining pieces of text, including line breaks,
+    /// each with its original span. Markers inside strings are not special-cased.
+    pub fn strip_comments(&self, markers: &[&str]) -> Vec<Spanned<&'a str>> {
+        let mut retained = vec![];
+        let mut rest = self.clone();
+        loop {
+            let comment = markers
+                .iter()
+                .filter(|marker| !marker.is_empty())
+                .filter_map(|marker| rest.find(marker))
+                .min();
+            let Some(start) = comment else {
+                break;
+            };
+            let (text, comment) = rest.split_at(start);
+            if !text.is_empty() {
+                retained.push(text);
+            }
+            let end = comment.find('\n').unwrap_or(comment.len());
+            // Keep the `\r` of a `\r\n` line break.
+            let end = if comment[..end].ends_with('\r') {
+                end - 1
+            } else {
+                end
+            };
+            rest = comment.split_at(end).1;
+        }
+        if !rest.is_empty() {
+            retained.push(rest);
+        }
+        retained
+    }
+}
@@ -0,0 +1,23 @@
+use crate::Span;
+use eyre::{eyre, Context, Result};
+use std::fmt::Display;
+
+/// Attach a span and a message to an error without building a `Spanned` first.
+pub trait SpanContext<T> {
+    fn with_span_context(self, span: Span, msg: impl Display) -> Result<T>;
+}
+
+impl<T, E> SpanContext<T> for std::result::Result<T, E>
+where
+    Self: Context<T, E>,
+{
+    fn with_span_context(self, span: Span, msg: impl Display) -> Result<T> {
+        self.wrap_err_with(|| format!("{span}: {msg}"))
+    }
+}
+
+impl<T> SpanContext<T> for Option<T> {
+    fn with_span_context(self, span: Span, msg: impl Display) -> Result<T> {
+        self.ok_or_else(|| eyre!("{span}: {msg}"))
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{Mapped, Segment, Spanned};
+
+impl Spanned<&str> {
+    /// Split into logical lines, joining every line that ends in `marker` (e.g. `\`)
+    /// with the next one. The marker and the line break are removed, and `Mapped::span_of`
+    /// maps ranges of a logical line back to the physical lines.
+    pub fn join_continuations(&self, marker: &str) -> Vec<Mapped> {
+        let mut logical = vec![];
+        let mut current: Option<Mapped> = None;
+        for line in self.clone().str_lines() {
+            let (text, continued) = match line.strip_suffix(marker) {
+                Some(text) => (text, true),
+                None => (line, false),
+            };
+            let mapped = current.get_or_insert_with(|| Mapped {
+                content: Spanned::new(String::new(), text.span.clone()),
+                segments: vec![],
+            });
+            let start = mapped.content.content.len();
+            mapped.content.content.push_str(text.content);
+            mapped.content.span = mapped.content.span.clone().join(&text.span);
+            mapped.segments.push(Segment {
+                output: start..mapped.content.content.len(),
+                span: text.span,
+                literal: true,
+            });
+            if !continued {
+                logical.extend(current.take());
+            }
+        }
+        logical.extend(current);
+        logical
+    }
+}
@@ -0,0 +1,213 @@
+//! A reader for comma (or otherwise) separated values, where every field knows its span.
+
+use crate::{err, Span, Spanned};
+use eyre::Result;
+use std::borrow::Cow;
+
+#[derive(Clone, Debug)]
+pub struct Record<'a> {
+    pub span: Span,
+    /// Quoted fields have their quotes removed. They are only owned if they contained
+    /// escaped (doubled) quotes.
+    pub fields: Vec<Spanned<Cow<'a, str>>>,
+}
+
+/// Iterate over the records of a file. Empty lines are skipped.
+/// After the first error, no further records are produced.
+pub fn parse(file: Spanned<&str>, delimiter: char) -> Records<'_> {
+    Records {
+        rest: file,
+        delimiter,
+    }
+}
+
+pub struct Records<'a> {
+    rest: Spanned<&'a str>,
+    delimiter: char,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(rest) = self
+            .rest
+            .strip_prefix("\n")
+            .or_else(|| self.rest.strip_prefix("\r\n"))
+        {
+            self.rest = rest;
+        }
+        if self.rest.is_empty() {
+            return None;
+        }
+        let result = self.record();
+        if result.is_err() {
+            self.rest = Spanned::new("", self.rest.span.clone().shrink_to_end());
+        }
+        Some(result)
+    }
+}
+
+impl<'a> Records<'a> {
+    fn record(&mut self) -> Result<Record<'a>> {
+        let start = self.rest.span.clone().shrink_to_start();
+        let mut fields: Vec<Spanned<Cow<'a, str>>> = vec![];
+        loop {
+            let field = if self.rest.starts_with("\"") {
+                self.quoted()?
+            } else {
+                let end = self
+                    .rest
+                    .find([self.delimiter, '\n'])
+                    .unwrap_or(self.rest.len());
+                let (field, rest) = self.rest.split_at(end);
+                self.rest = rest;
+                let field = field.strip_suffix("\r").unwrap_or(field);
+                field.map(Cow::Borrowed)
+            };
+            fields.push(field);
+            if self.rest.content.starts_with(self.delimiter) {
+                self.rest = self.rest.split_at(self.delimiter.len_utf8()).1;
+                continue;
+            }
+            if let Some(rest) = self.rest.strip_prefix("\n") {
+                self.rest = rest;
+            }
+            let span = start.join(&fields.last().unwrap().span);
+            return Ok(Record { span, fields });
+        }
+    }
+
+    fn quoted(&mut self) -> Result<Spanned<Cow<'a, str>>> {
+        let open = self.rest.span.clone().set_col_end_relative_to_start(1);
+        let (_, body) = self.rest.split_at(1);
+        let mut content = Cow::Borrowed("");
+        let mut pos = 0;
+        loop {
+            let Some(quote) = body[pos..].find('"') else {
+                return Err(err!(open, "unterminated quoted field"));
+            };
+            let quote = pos + quote;
+            if body[quote + 1..].starts_with('"') {
+                content.to_mut().push_str(&body[pos..=quote]);
+                pos = quote + 2;
+                continue;
+            }
+            let (inner, rest) = body.split_at(quote);
+            let (_, rest) = rest.split_at(1);
+            if !(rest.is_empty()
+                || rest.content.starts_with(self.delimiter)
+                || rest.starts_with("\n")
+                || rest.starts_with("\r\n"))
+            {
+                return Err(err!(
+                    rest.span.clone().shrink_to_start(),
+                    "expected `{}` or the end of the line after a quoted field",
+                    self.delimiter
+                ));
+            }
+            self.rest = rest.strip_prefix("\r").unwrap_or(rest);
+            let content = match content {
+                Cow::Borrowed(_) => Cow::Borrowed(inner.content),
+                Cow::Owned(mut owned) => {
+                    owned.push_str(&inner[pos..]);
+                    Cow::Owned(owned)
+                }
+            };
+            return Ok(Spanned::new(content, inner.span));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn records(s: &str) -> Vec<Vec<(String, std::ops::Range<usize>)>> {
+        let file = Spanned::new(
+            s,
+            Span {
+                file: Path::new("test.csv").into(),
+                bytes: 0..s.len(),
+            },
+        );
+        parse(file, ',')
+            .map(|record| {
+                record
+                    .unwrap()
+                    .fields
+                    .into_iter()
+                    .map(|field| (field.content.into_owned(), field.span.bytes))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn field(s: &str, bytes: std::ops::Range<usize>) -> (String, std::ops::Range<usize>) {
+        (s.to_string(), bytes)
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(
+            records("a,bc\nd,e"),
+            [
+                vec![field("a", 0..1), field("bc", 2..4)],
+                vec![field("d", 5..6), field("e", 7..8)],
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_lines() {
+        assert_eq!(
+            records("\na,b\n\n\nx,y\n"),
+            [
+                vec![field("a", 1..2), field("b", 3..4)],
+                vec![field("x", 7..8), field("y", 9..10)],
+            ]
+        );
+    }
+
+    #[test]
+    fn crlf() {
+        assert_eq!(
+            records("a,b\r\n\r\nx,y\r\n"),
+            [
+                vec![field("a", 0..1), field("b", 2..3)],
+                vec![field("x", 7..8), field("y", 9..10)],
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted() {
+        assert_eq!(
+            records("\"a,\"\"b\"\"\",c\r\n\"d\"\r\n"),
+            [
+                vec![field("a,\"b\"", 1..8), field("c", 10..11)],
+                vec![field("d", 14..15)],
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let s = "a,\"b\n";
+        let file = Spanned::new(
+            s,
+            Span {
+                file: Path::new("test.csv").into(),
+                bytes: 0..s.len(),
+            },
+        );
+        let mut records = parse(file, ',');
+        let err = records.next().unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("unterminated quoted field"),
+            "{err}"
+        );
+        assert!(records.next().is_none());
+    }
+}
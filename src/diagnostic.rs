@@ -0,0 +1,220 @@
+#[cfg(feature = "render")]
+use crate::Renderer;
+use crate::{source, FileId, Span};
+use std::{fmt::Display, sync::Arc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Level {
+    Error,
+    Warning,
+    Info,
+    Note,
+    Help,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info => "info",
+            Level::Note => "note",
+            Level::Help => "help",
+        })
+    }
+}
+
+/// A single message about a span, that does not necessarily abort anything.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    pub span: Span,
+    /// Explains what is wrong at `span`, shown right next to it.
+    pub primary_label: Option<String>,
+    /// Additional spans that explain the primary one, e.g. "previous definition here".
+    pub labels: Vec<Label>,
+    /// Free-standing lines rendered after everything else, like `= note: ...`.
+    pub footers: Vec<Footer>,
+    /// File contents taken by `snapshot_sources`, used instead of the files when rendering.
+    pub snapshots: Vec<(FileId, Arc<[u8]>)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Label {
+    pub level: Level,
+    pub message: String,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Footer {
+    pub level: Level,
+    pub message: String,
+    /// A link to further documentation, e.g. about the rule that was violated.
+    pub url: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            span,
+            primary_label: None,
+            labels: vec![],
+            footers: vec![],
+            snapshots: vec![],
+        }
+    }
+
+    /// Start building an error that does not point anywhere yet, see `primary`.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Level::Error, message, Span::default())
+    }
+
+    /// Start building a warning that does not point anywhere yet, see `primary`.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Level::Warning, message, Span::default())
+    }
+
+    /// Point the diagnostic at `span`, explaining what is wrong there with `label`.
+    pub fn primary(mut self, span: Span, label: impl Into<String>) -> Self {
+        self.span = span;
+        self.primary_label = Some(label.into());
+        self
+    }
+
+    /// Add a note pointing at `span`, e.g. "defined here".
+    pub fn secondary(self, span: Span, label: impl Into<String>) -> Self {
+        self.span_note(span, label)
+    }
+
+    pub fn span_label(mut self, level: Level, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            level,
+            message: message.into(),
+            span,
+        });
+        self
+    }
+
+    pub fn span_note(self, span: Span, message: impl Into<String>) -> Self {
+        self.span_label(Level::Note, span, message)
+    }
+
+    pub fn span_help(self, span: Span, message: impl Into<String>) -> Self {
+        self.span_label(Level::Help, span, message)
+    }
+
+    pub fn span_info(self, span: Span, message: impl Into<String>) -> Self {
+        self.span_label(Level::Info, span, message)
+    }
+
+    pub fn note(mut self, message: impl Into<String>) -> Self {
+        self.footers.push(Footer {
+            level: Level::Note,
+            message: message.into(),
+            url: None,
+        });
+        self
+    }
+
+    pub fn help(mut self, message: impl Into<String>) -> Self {
+        self.footers.push(Footer {
+            level: Level::Help,
+            message: message.into(),
+            url: None,
+        });
+        self
+    }
+
+    /// A help footer linking to documentation. Rendered as a hyperlink where supported.
+    pub fn help_url(mut self, message: impl Into<String>, url: impl Into<String>) -> Self {
+        self.footers.push(Footer {
+            level: Level::Help,
+            message: message.into(),
+            url: Some(url.into()),
+        });
+        self
+    }
+
+    /// Read the files the diagnostic points into now and keep their contents in the
+    /// diagnostic, so it still renders correctly if the files change or are deleted
+    /// before it is reported. Only rendering this diagnostic uses the snapshots, all
+    /// other lookups still see the current files. Files that can't be read are skipped.
+    pub fn snapshot_sources(mut self) -> Self {
+        let spans = std::iter::once(&self.span).chain(self.labels.iter().map(|label| &label.span));
+        for span in spans.filter(|span| !span.is_dummy()) {
+            if self.snapshots.iter().any(|(file, _)| *file == span.file) {
+                continue;
+            }
+            if let Ok(content) = source::read(&span.file) {
+                self.snapshots.push((span.file, content));
+            }
+        }
+        self
+    }
+
+    /// Render without any color codes, independent of the terminal.
+    #[cfg(feature = "render")]
+    pub fn render(&self) -> String {
+        Renderer::plain().render(self)
+    }
+
+    /// Render with ANSI color codes, even if the output is not a terminal.
+    #[cfg(feature = "render")]
+    pub fn render_colored(&self) -> String {
+        Renderer::colored().render(self)
+    }
+
+    /// For printing to stderr: `Renderer::auto`, or the plain `Display` output without
+    /// the `render` feature.
+    pub(crate) fn render_auto(&self) -> String {
+        #[cfg(feature = "render")]
+        return Renderer::auto().render(self);
+        #[cfg(not(feature = "render"))]
+        return self.to_string();
+    }
+}
+
+#[cfg(feature = "render")]
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Without the `render` feature there are no snippets, only the messages and the
+/// `path:line:column` of every span.
+#[cfg(not(feature = "render"))]
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = |span: &Span| match span.line_col(crate::Column::Char) {
+            Ok(pos) => format!("{}:{pos}", span.file.display()),
+            Err(_) => span.to_string(),
+        };
+        source::with_snapshots(&self.snapshots, || {
+            write!(f, "{}: {}", self.level, self.message)?;
+            if !self.span.is_dummy() {
+                write!(f, "\n --> {}", location(&self.span))?;
+            }
+            if let Some(label) = &self.primary_label {
+                write!(f, "\n  = {label}")?;
+            }
+            for label in &self.labels {
+                write!(f, "\n{}: {}", label.level, label.message)?;
+                if !label.span.is_dummy() {
+                    write!(f, "\n --> {}", location(&label.span))?;
+                }
+            }
+            for footer in &self.footers {
+                write!(f, "\n  = {}: {}", footer.level, footer.message)?;
+                if let Some(url) = &footer.url {
+                    write!(f, " ({url})")?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,180 @@
+//! A parser for unified diffs, as produced by `diff -u` or `git diff`.
+//!
+//! Lines know their position in the diff as well as their line numbers in the old and
+//! new file. Spans into those files are resolved through the source registry, so
+//! register the old contents under the old path if the file has changed on disk since.
+
+use crate::{err, Span, Spanned};
+use eyre::Result;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+pub struct FileDiff<'a> {
+    /// `None` for `/dev/null`, i.e. added files.
+    pub old_path: Option<Spanned<&'a str>>,
+    /// `None` for `/dev/null`, i.e. deleted files.
+    pub new_path: Option<Spanned<&'a str>>,
+    pub hunks: Vec<Hunk<'a>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hunk<'a> {
+    /// The `@@ -1,2 +1,3 @@` line.
+    pub header: Spanned<&'a str>,
+    pub lines: Vec<Line<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+#[derive(Clone, Debug)]
+pub struct Line<'a> {
+    pub kind: LineKind,
+    /// The line without its `+`, `-` or ` ` prefix, with a span into the diff.
+    pub text: Spanned<&'a str>,
+    /// 1-based line number in the old file, unless the line was added.
+    pub old_line: Option<usize>,
+    /// 1-based line number in the new file, unless the line was removed.
+    pub new_line: Option<usize>,
+}
+
+impl FileDiff<'_> {
+    /// The span of the line in the old file.
+    pub fn old_span(&self, line: &Line<'_>) -> Result<Span> {
+        resolve(&self.old_path, line.old_line, &line.text)
+    }
+
+    /// The span of the line in the new file.
+    pub fn new_span(&self, line: &Line<'_>) -> Result<Span> {
+        resolve(&self.new_path, line.new_line, &line.text)
+    }
+}
+
+fn resolve(
+    path: &Option<Spanned<&str>>,
+    line: Option<usize>,
+    text: &Spanned<&str>,
+) -> Result<Span> {
+    let (Some(path), Some(line)) = (path, line) else {
+        return Err(err!(text.span.clone(), "line does not exist in this file"));
+    };
+    Span::line(PathBuf::from(path.content), line)
+}
+
+/// Parse all file diffs. Lines outside of hunks, like `diff --git` or `index`, are ignored.
+pub fn parse(diff: Spanned<&str>) -> Result<Vec<FileDiff<'_>>> {
+    let mut files: Vec<FileDiff<'_>> = vec![];
+    // Lines left in the current hunk, in the old and the new file.
+    let mut remaining = (0, 0);
+    let mut next_line = (0, 0);
+    for line in diff.clone().str_lines() {
+        if remaining != (0, 0) {
+            let hunk = files.last_mut().unwrap().hunks.last_mut().unwrap();
+            if line.starts_with("\\") {
+                // `\ No newline at end of file`
+                continue;
+            }
+            let (kind, text) = match line.content.chars().next() {
+                Some('+') => (LineKind::Added, line.split_at(1).1),
+                Some('-') => (LineKind::Removed, line.split_at(1).1),
+                Some(' ') => (LineKind::Context, line.split_at(1).1),
+                // Some tools strip the trailing space of empty context lines.
+                None => (LineKind::Context, line.clone()),
+                _ => {
+                    return Err(err!(
+                        line.span,
+                        "expected a line starting with `+`, `-` or ` `"
+                    ))
+                }
+            };
+            let (in_old, in_new) = match kind {
+                LineKind::Context => (true, true),
+                LineKind::Removed => (true, false),
+                LineKind::Added => (false, true),
+            };
+            if (in_old && remaining.0 == 0) || (in_new && remaining.1 == 0) {
+                return Err(err!(
+                    line.span,
+                    "more lines than announced in the hunk header at {}",
+                    hunk.header.span
+                ));
+            }
+            let old_line = in_old.then_some(next_line.0);
+            let new_line = in_new.then_some(next_line.1);
+            remaining.0 -= usize::from(in_old);
+            remaining.1 -= usize::from(in_new);
+            next_line.0 += usize::from(in_old);
+            next_line.1 += usize::from(in_new);
+            hunk.lines.push(Line {
+                kind,
+                text,
+                old_line,
+                new_line,
+            });
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            files.push(FileDiff {
+                old_path: diff_path(path, "a/"),
+                new_path: None,
+                hunks: vec![],
+            });
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let Some(file) = files.last_mut().filter(|file| file.hunks.is_empty()) else {
+                return Err(err!(line.span, "`+++` line without preceding `---` line"));
+            };
+            file.new_path = diff_path(path, "b/");
+        } else if line.starts_with("@@ ") {
+            let Some(file) = files.last_mut() else {
+                return Err(err!(line.span, "hunk before the first `---` line"));
+            };
+            let ((old_start, old_len), (new_start, new_len)) = hunk_header(&line)?;
+            remaining = (old_len, new_len);
+            next_line = (old_start, new_start);
+            file.hunks.push(Hunk {
+                header: line,
+                lines: vec![],
+            });
+        }
+    }
+    if remaining != (0, 0) {
+        return Err(err!(
+            diff.span.shrink_to_end(),
+            "unexpected end of diff, hunk is missing lines"
+        ));
+    }
+    Ok(files)
+}
+
+/// Strip the timestamp that `diff -u` appends after a tab, and git's `a/` or `b/` prefix.
+fn diff_path<'a>(path: Spanned<&'a str>, prefix: &str) -> Option<Spanned<&'a str>> {
+    let path = match path.split_once("\t") {
+        Some((path, _timestamp)) => path,
+        None => path,
+    };
+    let path = path.trim_end();
+    if path.content == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path))
+}
+
+type LineRange = (usize, usize);
+
+/// Parse `@@ -old_start[,old_len] +new_start[,new_len] @@ ...`.
+fn hunk_header(line: &Spanned<&str>) -> Result<(LineRange, LineRange)> {
+    let invalid = || err!(line.span.clone(), "expected `@@ -start,len +start,len @@`");
+    let ranges = line[3..].split_once(" @@").ok_or_else(invalid)?.0;
+    let (old, new) = ranges.split_once(' ').ok_or_else(invalid)?;
+    let range = |range: &str, sign: char| -> Option<LineRange> {
+        let range = range.strip_prefix(sign)?;
+        let (start, len) = range.split_once(',').unwrap_or((range, "1"));
+        Some((start.parse().ok()?, len.parse().ok()?))
+    };
+    Ok((
+        range(old, '-').ok_or_else(invalid)?,
+        range(new, '+').ok_or_else(invalid)?,
+    ))
+}
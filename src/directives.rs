@@ -0,0 +1,68 @@
+//! Parsing of ui_test-style comment directives like `//@ key: value`.
+
+use crate::{err, Span, Spanned};
+use eyre::Result;
+
+/// A single `key` or `key: value` directive.
+#[derive(Clone, Debug)]
+pub struct Directive<'a> {
+    /// The entire directive, excluding the comment prefix.
+    pub span: Span,
+    pub key: Spanned<&'a str>,
+    /// `None` for flag directives that have no `:`.
+    pub value: Option<Spanned<&'a str>>,
+}
+
+/// Find all lines starting with `prefix` (after whitespace) and parse them as directives.
+/// Other lines are ignored.
+pub fn parse<'a>(
+    file: Spanned<&'a str>,
+    prefix: &'a str,
+) -> impl Iterator<Item = Result<Directive<'a>>> + 'a {
+    file.str_lines().filter_map(move |line| {
+        let directive = line.trim_start().strip_prefix(prefix)?.trim();
+        Some(parse_directive(directive))
+    })
+}
+
+fn parse_directive(directive: Spanned<&str>) -> Result<Directive<'_>> {
+    if directive.is_empty() {
+        return Err(err!(directive.span, "empty directive"));
+    }
+    let end = directive
+        .content
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(directive.len());
+    let (key, rest) = directive.split_at(end);
+    if key.is_empty() {
+        return Err(err!(
+            directive.span.clone().shrink_to_start(),
+            "expected a directive name"
+        ));
+    }
+    let rest = rest.trim_start();
+    let value = if rest.is_empty() {
+        None
+    } else if let Some(value) = rest.strip_prefix(":") {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(err!(
+                rest.span,
+                "missing value after `:` in `{}` directive",
+                *key
+            ));
+        }
+        Some(value)
+    } else {
+        return Err(err!(
+            rest.span,
+            "expected `:` after directive name `{}`",
+            *key
+        ));
+    };
+    Ok(Directive {
+        span: directive.span,
+        key,
+        value,
+    })
+}
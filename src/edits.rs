@@ -0,0 +1,74 @@
+use crate::Span;
+use std::{ops::Range, path::PathBuf};
+
+/// Replacements applied to a file, used to move spans computed before the
+/// edits to where their text is after the edits.
+#[derive(Clone, Debug)]
+pub struct Edits {
+    file: PathBuf,
+    /// Sorted, non-overlapping ranges of the original file and the length of their
+    /// replacement.
+    replacements: Vec<(Range<usize>, usize)>,
+}
+
+impl Edits {
+    pub fn new(file: impl Into<PathBuf>) -> Self {
+        Self {
+            file: file.into(),
+            replacements: vec![],
+        }
+    }
+
+    /// Record that `range` (in the original file) was replaced by `replacement`.
+    #[track_caller]
+    pub fn replace(&mut self, range: Range<usize>, replacement: &str) {
+        let i = self
+            .replacements
+            .partition_point(|(r, _)| r.start < range.start);
+        let overlaps =
+            |other: &(Range<usize>, usize)| other.0.start < range.end && range.start < other.0.end;
+        assert!(
+            !self.replacements.get(i).is_some_and(overlaps)
+                && !i
+                    .checked_sub(1)
+                    .is_some_and(|i| overlaps(&self.replacements[i])),
+            "overlapping replacement {range:?}"
+        );
+        self.replacements.insert(i, (range, replacement.len()));
+    }
+
+    /// The span's location after the edits. Spans into other files are unchanged.
+    /// Returns `None` if the span starts or ends inside replaced text.
+    pub fn map(&self, span: &Span) -> Option<Span> {
+        if span.is_dummy() || *span.file != *self.file {
+            return Some(span.clone());
+        }
+        let start = self.map_pos(span.bytes.start, false)?;
+        let end = self.map_pos(span.bytes.end, true)?;
+        Some(Span {
+            file: span.file,
+            bytes: start..end,
+        })
+    }
+
+    /// Insertions at `pos` happen after it if it is the end of a span,
+    /// and before it if it is the start of a span.
+    fn map_pos(&self, pos: usize, is_end: bool) -> Option<usize> {
+        let mut new = pos;
+        for (range, len) in &self.replacements {
+            let before = if is_end {
+                pos <= range.start
+            } else {
+                pos <= range.start && pos < range.end
+            };
+            if before {
+                break;
+            }
+            if pos < range.end {
+                return None;
+            }
+            new = new - range.len() + len;
+        }
+        Some(new)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Diagnostic, Level, Span};
+use std::{collections::HashSet, fmt::Display, path::Path};
+
+/// Collects diagnostics over the course of a run instead of aborting at the first error.
+///
+/// Identical diagnostics are only recorded once. If a maximum number of errors is
+/// configured, the first error beyond it is replaced by a final "too many errors"
+/// diagnostic, and nothing is collected after that.
+///
+/// With the `tracing` feature, every recorded diagnostic is also emitted as a `tracing` event.
+#[derive(Debug, Default)]
+pub struct Emitter {
+    diagnostics: Vec<Diagnostic>,
+    seen: HashSet<Diagnostic>,
+    max_errors: Option<usize>,
+    errors: usize,
+    warnings: usize,
+    /// Errors that were dropped because the error limit was reached.
+    suppressed: usize,
+}
+
+impl Emitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
+    /// Record a diagnostic. Returns `false` if it was dropped because it is a duplicate
+    /// or because the error limit has been reached.
+    pub fn emit(&mut self, diagnostic: Diagnostic) -> bool {
+        // Duplicates are dropped before the error limit is checked, so they never count
+        // as suppressed errors.
+        if self.seen.contains(&diagnostic) {
+            return false;
+        }
+        if self.is_aborted() {
+            if diagnostic.level == Level::Error {
+                self.suppressed += 1;
+            }
+            return false;
+        }
+        if let Some(max) = self.max_errors {
+            if diagnostic.level == Level::Error && self.errors >= max {
+                self.suppressed += 1;
+                self.diagnostics.push(Diagnostic::new(
+                    Level::Error,
+                    format!("too many errors, stopping after {max}"),
+                    Span::default(),
+                ));
+                return false;
+            }
+        }
+        self.seen.insert(diagnostic.clone());
+        match diagnostic.level {
+            Level::Error => self.errors += 1,
+            Level::Warning => self.warnings += 1,
+            Level::Info | Level::Note | Level::Help => {}
+        }
+        #[cfg(feature = "tracing")]
+        diagnostic.trace();
+        self.diagnostics.push(diagnostic);
+        true
+    }
+
+    pub fn error(&mut self, span: Span, message: impl Into<String>) -> bool {
+        self.emit(Diagnostic::new(Level::Error, message, span))
+    }
+
+    pub fn warning(&mut self, span: Span, message: impl Into<String>) -> bool {
+        self.emit(Diagnostic::new(Level::Warning, message, span))
+    }
+
+    pub fn note(&mut self, span: Span, message: impl Into<String>) -> bool {
+        self.emit(Diagnostic::new(Level::Note, message, span))
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.warnings
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.max_errors.is_some_and(|max| self.errors >= max)
+    }
+
+    /// Whether an error beyond the limit was emitted, so batch tools can stop early.
+    pub fn is_aborted(&self) -> bool {
+        self.suppressed > 0
+    }
+
+    /// The number of errors dropped because the error limit was reached.
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// Order diagnostics by file and position within the file, no matter in which order
+    /// they were emitted. Diagnostics without a span keep their relative order at the end.
+    pub fn sort_by_span(&mut self) {
+        self.diagnostics
+            .sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+    }
+
+    /// The diagnostics of each file, in source order, with files sorted by path.
+    /// Diagnostics without a span are grouped at the end under `None`.
+    pub fn group_by_file(&self) -> Vec<(Option<&Path>, Vec<&Diagnostic>)> {
+        let mut diagnostics: Vec<_> = self.diagnostics.iter().collect();
+        diagnostics.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+        let mut groups: Vec<(Option<&Path>, Vec<&Diagnostic>)> = vec![];
+        for diagnostic in diagnostics {
+            let file = (!diagnostic.span.is_dummy()).then_some(&*diagnostic.span.file);
+            match groups.last_mut() {
+                Some((last, group)) if *last == file => group.push(diagnostic),
+                _ => groups.push((file, vec![diagnostic])),
+            }
+        }
+        groups
+    }
+
+    /// A line like `2 warnings, 1 error emitted`, or `None` if there was nothing to report.
+    /// Mentions how many errors were suppressed if the error limit was exceeded.
+    pub fn summary(&self) -> Option<String> {
+        let plural = |n: usize, what: &str| match n {
+            1 => format!("1 {what}"),
+            n => format!("{n} {what}s"),
+        };
+        let counts: Vec<_> = [(self.warnings, "warning"), (self.errors, "error")]
+            .into_iter()
+            .filter(|&(n, _)| n > 0)
+            .map(|(n, what)| plural(n, what))
+            .collect();
+        if counts.is_empty() {
+            return None;
+        }
+        let mut summary = format!("{} emitted", counts.join(", "));
+        if self.suppressed > 0 {
+            summary.push_str(&format!(
+                ", {} suppressed",
+                plural(self.suppressed, "error")
+            ));
+        }
+        Some(summary)
+    }
+}
+
+fn sort_key(diagnostic: &Diagnostic) -> (bool, &Path, usize, usize) {
+    let span = &diagnostic.span;
+    (
+        span.is_dummy(),
+        &span.file,
+        span.bytes.start,
+        span.bytes.end,
+    )
+}
+
+impl Display for Emitter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}\n")?;
+        }
+        if let Some(summary) = self.summary() {
+            writeln!(f, "{summary}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{register_source, FileId, Span, Spanned};
+use eyre::{Context, Result};
+use std::path::PathBuf;
+
+impl Spanned<String> {
+    /// Like `std::env::args`, but every argument points into a `<command line>`
+    /// source containing all arguments joined by spaces. Invalid UTF-8 is replaced.
+    pub fn args() -> Vec<Self> {
+        let file = FileId::from("<command line>");
+        let mut command_line = String::new();
+        let mut args = vec![];
+        for arg in std::env::args_os() {
+            if !command_line.is_empty() {
+                command_line.push(' ');
+            }
+            let arg = arg.to_string_lossy().into_owned();
+            let start = command_line.len();
+            command_line.push_str(&arg);
+            let span = Span {
+                file,
+                bytes: start..command_line.len(),
+            };
+            args.push(Spanned::new(arg, span));
+        }
+        register_source(&*file, command_line.as_bytes());
+        args
+    }
+
+    /// Reads the environment variable `var`, with a span covering a `<env:NAME>`
+    /// source that contains the value.
+    pub fn from_env(var: &str) -> Result<Self> {
+        let content = std::env::var(var).with_context(|| format!("environment variable {var}"))?;
+        let file = PathBuf::from(format!("<env:{var}>"));
+        register_source(&file, content.as_bytes());
+        let span = Span {
+            file: file.into(),
+            bytes: 0..content.len(),
+        };
+        Ok(Spanned::new(content, span))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Diagnostic, Emitter};
+use eyre::Report;
+use std::process::{ExitCode, Termination};
+
+/// An error that ends the process with a specific exit code, printing only its message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExitError {
+    pub message: String,
+    pub code: u8,
+}
+
+impl ExitError {
+    pub fn new(message: impl Into<String>, code: u8) -> Self {
+        Self {
+            message: message.into(),
+            code,
+        }
+    }
+
+    pub fn with_code(mut self, code: u8) -> Self {
+        self.code = code;
+        self
+    }
+}
+
+impl From<Diagnostic> for ExitError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self::new(diagnostic.render_auto(), 1)
+    }
+}
+
+/// Prints all diagnostics and the summary, like `Emitter`'s `Display` impl.
+impl From<Emitter> for ExitError {
+    fn from(emitter: Emitter) -> Self {
+        Self::new(emitter.to_string().trim_end(), 1)
+    }
+}
+
+/// Prints the error and its causes on a single line, without a backtrace.
+impl From<Report> for ExitError {
+    fn from(report: Report) -> Self {
+        Self::new(format!("error: {report:#}"), 1)
+    }
+}
+
+/// Return type for `main` that prints errors without the `Error: ` prefix and `Debug`
+/// formatting of `Result`, and exits with the error's exit code. Convert with `.into()`
+/// from any `Result<(), E>` where `E: Into<ExitError>`.
+#[derive(Debug)]
+pub enum MainResult {
+    Ok,
+    Err(ExitError),
+}
+
+impl<E: Into<ExitError>> From<Result<(), E>> for MainResult {
+    fn from(result: Result<(), E>) -> Self {
+        match result {
+            Ok(()) => MainResult::Ok,
+            Err(err) => MainResult::Err(err.into()),
+        }
+    }
+}
+
+impl Termination for MainResult {
+    fn report(self) -> ExitCode {
+        match self {
+            MainResult::Ok => ExitCode::SUCCESS,
+            MainResult::Err(err) => {
+                eprintln!("{}", err.message);
+                ExitCode::from(err.code)
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{err, Mapped, Segment, Spanned};
+use eyre::Result;
+
+impl Spanned<&str> {
+    /// Replace every `${NAME}` with the value returned by `lookup`.
+    /// Fails with an error pointing at the reference if `lookup` returns `None`.
+    pub fn expand_vars(&self, mut lookup: impl FnMut(&str) -> Option<String>) -> Result<Mapped> {
+        let mut content = String::new();
+        let mut segments = vec![];
+        let mut rest = self.clone();
+        while let Some(start) = rest.find("${") {
+            let (literal, var) = rest.split_at(start);
+            if !literal.is_empty() {
+                segments.push(Segment {
+                    output: content.len()..content.len() + literal.len(),
+                    span: literal.span,
+                    literal: true,
+                });
+                content.push_str(literal.content);
+            }
+            let Some(end) = var.find('}') else {
+                return Err(err!(
+                    var.span.clone().set_col_end_relative_to_start(2),
+                    "unterminated variable reference"
+                ));
+            };
+            let (var, after) = var.split_at(end + 1);
+            let name = &var[2..end];
+            let Some(value) = lookup(name) else {
+                return Err(err!(var.span, "undefined variable `{name}`"));
+            };
+            segments.push(Segment {
+                output: content.len()..content.len() + value.len(),
+                span: var.span,
+                literal: false,
+            });
+            content.push_str(&value);
+            rest = after;
+        }
+        if !rest.is_empty() {
+            segments.push(Segment {
+                output: content.len()..content.len() + rest.len(),
+                span: rest.span,
+                literal: true,
+            });
+            content.push_str(rest.content);
+        }
+        Ok(Mapped {
+            content: Spanned::new(content, self.span.clone()),
+            segments,
+        })
+    }
+
+    /// `expand_vars` using the process environment.
+    pub fn expand_env_vars(&self) -> Result<Mapped> {
+        self.expand_vars(|name| std::env::var(name).ok())
+    }
+}
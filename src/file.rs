@@ -0,0 +1,177 @@
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+/// An interned file path. Copying one is free, comparing or hashing two only looks at
+/// the pointer, so the string operations on `Spanned<&str>`, which all copy their span,
+/// neither allocate nor touch an atomic reference count.
+///
+/// Every distinct path is allocated once and lives for the rest of the process.
+#[derive(Clone, Copy)]
+pub struct FileId(&'static Path);
+
+fn interned() -> &'static RwLock<HashSet<&'static Path>> {
+    static INTERNED: OnceLock<RwLock<HashSet<&'static Path>>> = OnceLock::new();
+    INTERNED.get_or_init(Default::default)
+}
+
+impl FileId {
+    pub fn new(path: &Path) -> Self {
+        if let Some(&path) = interned()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(path)
+        {
+            return Self(path);
+        }
+        let mut interned = interned().write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have interned it in the meantime.
+        if let Some(&path) = interned.get(path) {
+            return Self(path);
+        }
+        let path: &'static Path = Box::leak(path.into());
+        interned.insert(path);
+        Self(path)
+    }
+
+    /// The file of dummy spans.
+    pub fn empty() -> Self {
+        static EMPTY: OnceLock<FileId> = OnceLock::new();
+        *EMPTY.get_or_init(|| Self::new(Path::new("")))
+    }
+
+    pub fn path(self) -> &'static Path {
+        self.0
+    }
+}
+
+impl Default for FileId {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Deref for FileId {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        self.0
+    }
+}
+
+impl AsRef<Path> for FileId {
+    fn as_ref(&self) -> &Path {
+        self.0
+    }
+}
+
+impl AsRef<OsStr> for FileId {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+/// Interning guarantees that equal paths share a pointer.
+impl PartialEq for FileId {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for FileId {}
+
+impl Hash for FileId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.0, state)
+    }
+}
+
+/// Orders by path, so sorting is deterministic across runs.
+impl PartialOrd for FileId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FileId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl std::fmt::Debug for FileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<&Path> for FileId {
+    fn from(path: &Path) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<PathBuf> for FileId {
+    fn from(path: PathBuf) -> Self {
+        Self::new(&path)
+    }
+}
+
+impl From<&PathBuf> for FileId {
+    fn from(path: &PathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<Arc<Path>> for FileId {
+    fn from(path: Arc<Path>) -> Self {
+        Self::new(&path)
+    }
+}
+
+impl From<&str> for FileId {
+    fn from(path: &str) -> Self {
+        Self::new(Path::new(path))
+    }
+}
+
+impl From<String> for FileId {
+    fn from(path: String) -> Self {
+        Self::new(Path::new(&path))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FileId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PathBuf::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileId;
+    use std::path::Path;
+
+    #[test]
+    fn interned() {
+        let a = FileId::from("src/lib.rs");
+        let b = FileId::from(Path::new("src/lib.rs").to_path_buf());
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.path(), b.path()));
+        assert_ne!(a, FileId::from("src/main.rs"));
+        assert_eq!(FileId::default(), FileId::from(""));
+    }
+}
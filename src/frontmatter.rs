@@ -0,0 +1,68 @@
+use crate::Spanned;
+
+impl<'a> Spanned<&'a str> {
+    /// Split off front matter fenced by `---` (YAML) or `+++` (TOML) lines at the very
+    /// start of the file. Returns the text between the fences and the body after
+    /// the closing fence, or `None` and the entire text if there is no front matter.
+    pub fn split_front_matter(&self) -> (Option<Spanned<&'a str>>, Spanned<&'a str>) {
+        let no_front_matter = (None, self.clone());
+        let Some(fence) = ["---", "+++"]
+            .into_iter()
+            .find(|fence| self.starts_with(fence))
+        else {
+            return no_front_matter;
+        };
+        let mut lines = self.content.split_inclusive('\n');
+        if lines.next().map(str::trim_end) != Some(fence) {
+            return no_front_matter;
+        }
+        // A file that is only a fence has no closing fence.
+        let Some(newline) = self.content.find('\n') else {
+            return no_front_matter;
+        };
+        let start = newline + 1;
+        let mut pos = start;
+        for line in lines {
+            if line.trim_end() == fence {
+                let (front_matter, body) = self.split_at(pos + line.len());
+                let front_matter = front_matter.split_at(pos).0.split_at(start).1;
+                return (Some(front_matter), body);
+            }
+            pos += line.len();
+        }
+        no_front_matter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Span, Spanned};
+    use std::path::Path;
+
+    fn split(s: &str) -> (Option<&str>, &str) {
+        let span = Span {
+            file: Path::new("test.md").into(),
+            bytes: 0..s.len(),
+        };
+        let (front_matter, body) = Spanned::new(s, span).split_front_matter();
+        (
+            front_matter.map(|front_matter| front_matter.content),
+            body.content,
+        )
+    }
+
+    #[test]
+    fn front_matter() {
+        assert_eq!(split("---\na: 1\n---\nbody"), (Some("a: 1\n"), "body"));
+        assert_eq!(split("+++\r\na = 1\r\n+++\r\n"), (Some("a = 1\r\n"), ""));
+        assert_eq!(split("body"), (None, "body"));
+        assert_eq!(split("---\nunclosed"), (None, "---\nunclosed"));
+    }
+
+    #[test]
+    fn fence_only() {
+        assert_eq!(split("---"), (None, "---"));
+        assert_eq!(split("+++"), (None, "+++"));
+        assert_eq!(split("---\n"), (None, "---\n"));
+    }
+}
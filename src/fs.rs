@@ -0,0 +1,102 @@
+//! Wrappers around `std::fs` whose errors mention the operation and the path.
+
+use crate::{Span, Spanned};
+use eyre::{Context, Result};
+use std::path::{Path, PathBuf};
+
+fn context(operation: &str, path: &Path) -> String {
+    format!("failed to {operation} {}", path.display())
+}
+
+/// Like `Spanned::read_from_file`: the span covers the whole file.
+pub fn read(path: impl Into<PathBuf>) -> Result<Spanned<Vec<u8>>> {
+    let file = path.into();
+    let content = std::fs::read(&file).with_context(|| context("read", &file))?;
+    let span = Span {
+        file: file.into(),
+        bytes: 0..content.len(),
+    };
+    Ok(Spanned::new(content, span))
+}
+
+/// Reads the file and requires it to be UTF-8. The error points at the first invalid byte.
+pub fn read_to_string(path: impl Into<PathBuf>) -> Result<Spanned<String>> {
+    let Spanned { span, content } = read(path)?;
+    match String::from_utf8(content) {
+        Ok(content) => Ok(Spanned::new(content, span)),
+        Err(err) => {
+            let start = err.utf8_error().valid_up_to();
+            let end = start + err.utf8_error().error_len().unwrap_or(1);
+            let span = Span {
+                bytes: start..end.min(span.bytes.end),
+                ..span
+            };
+            Err(err.utf8_error()).with_context(|| span)
+        }
+    }
+}
+
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    std::fs::write(path, contents).with_context(|| context("write", path))
+}
+
+pub fn read_dir(path: impl AsRef<Path>) -> Result<impl Iterator<Item = Result<std::fs::DirEntry>>> {
+    let path = path.as_ref().to_path_buf();
+    let entries = std::fs::read_dir(&path).with_context(|| context("read directory", &path))?;
+    Ok(entries.map(move |entry| entry.with_context(|| context("read directory", &path))))
+}
+
+pub fn create_dir_all(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path).with_context(|| context("create directory", path))
+}
+
+impl Spanned<String> {
+    /// Like `Spanned::read_from_file`, but requires the file to be UTF-8. The error for
+    /// invalid UTF-8 points at the first invalid byte.
+    pub fn read_string_from_file(path: impl Into<PathBuf>) -> Result<Self> {
+        read_to_string(path)
+    }
+
+    /// Read all files below `root` for which `filter` returns `true`, like `read_to_string`.
+    /// Directories are visited in sorted order, so the order of the files is deterministic.
+    /// Symlinks to directories are skipped, so links to an ancestor can't cause a loop.
+    pub fn read_dir_recursive(
+        root: impl Into<PathBuf>,
+        mut filter: impl FnMut(&Path) -> bool,
+    ) -> impl Iterator<Item = Result<Self>> {
+        let mut dirs = vec![root.into()];
+        let mut files = vec![].into_iter();
+        std::iter::from_fn(move || loop {
+            if let Some(file) = files.find(|file: &PathBuf| filter(file)) {
+                return Some(read_to_string(file));
+            }
+            let dir = dirs.pop()?;
+            let entries =
+                match read_dir(&dir).and_then(|entries| entries.collect::<Result<Vec<_>>>()) {
+                    Ok(entries) => entries,
+                    Err(err) => return Some(Err(err)),
+                };
+            let mut subdirs = vec![];
+            let mut dir_files = vec![];
+            for entry in entries {
+                let path = entry.path();
+                // Unlike `Path::is_dir`, this does not follow symlinks.
+                let file_type = match entry.file_type() {
+                    Ok(file_type) => file_type,
+                    Err(err) => return Some(Err(err).with_context(|| context("read", &path))),
+                };
+                if file_type.is_dir() {
+                    subdirs.push(path);
+                } else if !(file_type.is_symlink() && path.is_dir()) {
+                    dir_files.push(path);
+                }
+            }
+            subdirs.sort();
+            dir_files.sort();
+            dirs.extend(subdirs.into_iter().rev());
+            files = dir_files.into_iter();
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{source, Column, Diagnostic, Emitter, Level, Renderer};
+
+impl Diagnostic {
+    /// Render as a GitHub Actions workflow command, see `Renderer::render_github`.
+    pub fn render_github(&self) -> String {
+        Renderer::plain().render_github(self)
+    }
+}
+
+impl Renderer {
+    /// Render as a GitHub Actions workflow command (`::error file=...::message`), so the
+    /// diagnostic shows up as an annotation on the pull request diff.
+    /// Labels and footers are appended to the message.
+    /// Paths are remapped like in `render`.
+    pub fn render_github(&self, diagnostic: &Diagnostic) -> String {
+        source::with_snapshots(&diagnostic.snapshots, || {
+            let command = match diagnostic.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+                Level::Info | Level::Note | Level::Help => "notice",
+            };
+            let mut properties = vec![];
+            if !diagnostic.span.is_dummy() {
+                properties.push(format!(
+                    "file={}",
+                    escape_property(&self.path(&diagnostic.span.file))
+                ));
+                if let (Ok(start), Ok(end)) = (
+                    diagnostic.span.line_col(Column::Char),
+                    diagnostic.span.end_line_col(Column::Char),
+                ) {
+                    properties.push(format!("line={}", start.line));
+                    properties.push(format!("endLine={}", end.line));
+                    // GitHub only supports column ranges within a single line.
+                    if start.line == end.line {
+                        // GitHub's `endColumn` is inclusive.
+                        let end_column = end.column.saturating_sub(1).max(start.column);
+                        properties.push(format!("col={}", start.column));
+                        properties.push(format!("endColumn={end_column}"));
+                    }
+                }
+            }
+            let mut message = diagnostic.message.clone();
+            if let Some(label) = &diagnostic.primary_label {
+                message.push_str(&format!(": {label}"));
+            }
+            for label in &diagnostic.labels {
+                message.push_str(&format!("\n{}: {}", label.level, label.message));
+                if !label.span.is_dummy() {
+                    message.push_str(&format!(" ({})", self.location(&label.span)));
+                }
+            }
+            for footer in &diagnostic.footers {
+                message.push_str(&format!("\n{}: {}", footer.level, footer.message));
+                if let Some(url) = &footer.url {
+                    message.push_str(&format!(" ({url})"));
+                }
+            }
+            let properties = properties.join(",");
+            let separator = if properties.is_empty() { "" } else { " " };
+            format!(
+                "::{command}{separator}{properties}::{}",
+                escape_data(&message)
+            )
+        })
+    }
+}
+
+impl Emitter {
+    /// All diagnostics as GitHub Actions workflow commands, one per line.
+    pub fn render_github(&self) -> String {
+        self.diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.render_github() + "\n")
+            .collect()
+    }
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{register_source, Diagnostic, Span};
+    use std::path::Path;
+
+    #[test]
+    fn columns_and_labels() {
+        let file = "<github test>";
+        register_source(file, &b"abcdef\n"[..]);
+        let span = |bytes| Span {
+            file: Path::new(file).into(),
+            bytes,
+        };
+        let diagnostic = Diagnostic::error("bad")
+            .primary(span(4..5), "here")
+            .secondary(span(0..0), "start")
+            .secondary(Span::default(), "elsewhere");
+        assert_eq!(
+            diagnostic.render_github(),
+            "::error file=<github test>,line=1,endLine=1,col=5,endColumn=5::bad: here\
+             %0Anote: start (<github test>:1:1)%0Anote: elsewhere"
+        );
+        let empty = Diagnostic::error("empty").primary(span(2..2), "here");
+        assert!(empty.render_github().contains("col=3,endColumn=3"));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{source, Diagnostic, Level, Renderer, Span};
+use std::fmt::Write;
+
+impl Diagnostic {
+    /// Render as HTML, see `Renderer::render_html`.
+    pub fn render_html(&self) -> String {
+        Renderer::plain().render_html(self)
+    }
+}
+
+impl Renderer {
+    /// Render as a self-contained HTML fragment, with the source snippets escaped and
+    /// the spans highlighted with `<mark>`. Elements have classes like `diagnostic`,
+    /// `error` or `snippet` for styling.
+    /// Paths are remapped like in `render`.
+    pub fn render_html(&self, diagnostic: &Diagnostic) -> String {
+        source::with_snapshots(&diagnostic.snapshots, || {
+            let mut out = format!("<div class=\"diagnostic {}\">\n", diagnostic.level);
+            message(
+                self,
+                &mut out,
+                diagnostic.level,
+                &diagnostic.message,
+                &diagnostic.span,
+            );
+            if let Some(label) = &diagnostic.primary_label {
+                writeln!(out, "<p class=\"label\">{}</p>", escape(label)).unwrap();
+            }
+            for label in &diagnostic.labels {
+                message(self, &mut out, label.level, &label.message, &label.span);
+            }
+            if !diagnostic.footers.is_empty() {
+                out.push_str("<ul class=\"footers\">\n");
+                for footer in &diagnostic.footers {
+                    let link = match &footer.url {
+                        Some(url) => format!(" <a href=\"{0}\">{0}</a>", escape(url)),
+                        None => String::new(),
+                    };
+                    writeln!(
+                        out,
+                        "<li class=\"{0}\"><strong>{0}</strong>: {1}{link}</li>",
+                        footer.level,
+                        escape(&footer.message)
+                    )
+                    .unwrap();
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("</div>\n");
+            out
+        })
+    }
+}
+
+fn message(renderer: &Renderer, out: &mut String, level: Level, message: &str, span: &Span) {
+    writeln!(
+        out,
+        "<p class=\"message {level}\"><strong>{level}</strong>: {}</p>",
+        escape(message)
+    )
+    .unwrap();
+    if span.is_dummy() {
+        return;
+    }
+    let location = renderer.location(span);
+    writeln!(out, "<p class=\"location\">{}</p>", escape(&location)).unwrap();
+    if let Ok(snippet) = span.snippet() {
+        let text = &snippet.text;
+        let highlight = snippet.highlight;
+        writeln!(
+            out,
+            "<pre class=\"snippet\"><code>{}<mark>{}</mark>{}</code></pre>",
+            escape(&text[..highlight.start]),
+            escape(&text[highlight.clone()]),
+            escape(&text[highlight.end..]),
+        )
+        .unwrap();
+    }
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,74 @@
+use crate::{Span, Spanned};
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+impl Spanned<&str> {
+    /// The lines after the line containing `line` that are indented further than it,
+    /// like a YAML or Python block. Blank lines are only included if more indented lines
+    /// follow. `self` must be the text that `line` points into, e.g. the whole file.
+    /// If there are no such lines, the result is empty and at the end of `line`'s line.
+    /// If `line` is not inside `self`, the result is empty and at the end of `self`.
+    pub fn indented_block(&self, line: &Span) -> Self {
+        let offset = line
+            .bytes
+            .start
+            .checked_sub(self.span.bytes.start)
+            .filter(|&offset| line.file == self.span.file && self.is_char_boundary(offset));
+        let Some(offset) = offset else {
+            return self.split_at(self.len()).1;
+        };
+        let line_start = self[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self[offset..].find('\n').map_or(self.len(), |i| offset + i);
+        let indent = indentation(&self[line_start..line_end]);
+        let mut block = None;
+        let mut pos = line_end;
+        while pos < self.len() {
+            let start = pos + 1;
+            let end = self[start..].find('\n').map_or(self.len(), |i| start + i);
+            let text = self[start..end].trim_end_matches('\r');
+            if !text.trim().is_empty() {
+                if indentation(text) <= indent {
+                    break;
+                }
+                let block_start = block.map_or(start, |(block_start, _)| block_start);
+                block = Some((block_start, start + text.len()));
+            }
+            pos = end;
+        }
+        let (start, end) = block.unwrap_or((line_end, line_end));
+        self.split_at(end).0.split_at(start).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Span, Spanned};
+    use std::path::Path;
+
+    fn spanned(s: &str, start: usize) -> Spanned<&str> {
+        let span = Span {
+            file: Path::new("test").into(),
+            bytes: start..start + s.len(),
+        };
+        Spanned::new(s, span)
+    }
+
+    #[test]
+    fn block() {
+        let file = spanned("a:\n  b\n\n  c\nd", 10);
+        let line = file.split_at(1).0.span;
+        let block = file.indented_block(&line);
+        assert_eq!((block.content, block.span.bytes), ("  b\n\n  c", 13..21));
+    }
+
+    #[test]
+    fn line_outside() {
+        let file = spanned("a:\n  b", 10);
+        for line in [spanned("x", 0).span, spanned("x", 100).span] {
+            let block = file.indented_block(&line);
+            assert_eq!((block.content, block.span.bytes), ("", 16..16));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! A tiny parser for line based `key = value` configs with `[section]` headers.
+//!
+//! Lines starting with `#` or `;` are comments.
+
+use crate::{err, Span, Spanned};
+use eyre::Result;
+
+#[derive(Clone, Debug, Default)]
+pub struct Ini {
+    /// The first section has no name and contains all entries before the first header.
+    pub sections: Vec<Section>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub name: Option<Spanned<String>>,
+    /// From the header to the end of the last entry.
+    pub span: Span,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub key: Spanned<String>,
+    pub value: Spanned<String>,
+}
+
+impl Ini {
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|section| section.name.as_ref().is_some_and(|n| n == name))
+    }
+}
+
+impl Section {
+    /// The value of the last entry with the given key.
+    pub fn get(&self, key: &str) -> Option<&Spanned<String>> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.key == key)
+            .map(|entry| &entry.value)
+    }
+}
+
+pub fn parse(file: Spanned<&str>) -> Result<Ini> {
+    let mut sections = vec![Section {
+        name: None,
+        span: file.span.clone().shrink_to_start(),
+        entries: vec![],
+    }];
+    for line in file.str_lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#") || line.starts_with(";") {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("[") {
+            let Some(name) = header.strip_suffix("]") else {
+                return Err(err!(line.span, "unclosed section header"));
+            };
+            sections.push(Section {
+                name: Some(name.trim().into()),
+                span: line.span,
+                entries: vec![],
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once("=") else {
+            return Err(err!(line.span, "expected `key = value`"));
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(err!(key.span, "missing key before `=`"));
+        }
+        let section = sections.last_mut().unwrap();
+        section.span = section.span.clone().join(&line.span);
+        section.entries.push(Entry {
+            key: key.into(),
+            value: value.trim().into(),
+        });
+    }
+    Ok(Ini { sections })
+}
@@ -0,0 +1,96 @@
+//! Compact serialization of spans, with every file path written only once.
+
+use crate::FileId;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf};
+
+/// Serializes the wrapped value with the file path of each span written out only the
+/// first time it occurs, and as an index into the paths seen so far afterwards.
+///
+/// Both sides must use the wrapper, and the format must deserialize values in the order
+/// they were serialized, which holds for the usual formats like bincode or JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Interned<T>(pub T);
+
+#[derive(Default)]
+struct Table {
+    indices: HashMap<FileId, u32>,
+    paths: Vec<FileId>,
+}
+
+thread_local! {
+    /// Only set while (de)serializing an `Interned` value.
+    static TABLE: RefCell<Option<Table>> = const { RefCell::new(None) };
+}
+
+#[derive(Serialize, Deserialize)]
+enum FileRef<P> {
+    Path(P),
+    Index(u32),
+}
+
+/// Runs `f` with a fresh table, restoring the outer one afterwards, even on panics.
+fn with_table<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Table>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            TABLE.with(|table| *table.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(TABLE.with(|table| table.replace(Some(Table::default()))));
+    f()
+}
+
+impl<T: Serialize> Serialize for Interned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        with_table(|| self.0.serialize(serializer))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Interned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        with_table(|| T::deserialize(deserializer).map(Interned))
+    }
+}
+
+/// `serialize_with` for `Span::file`.
+pub(crate) fn serialize<S: Serializer>(file: &FileId, serializer: S) -> Result<S::Ok, S::Error> {
+    let file_ref = TABLE.with(|table| {
+        let table = &mut *table.borrow_mut();
+        let table = table.as_mut()?;
+        if let Some(&index) = table.indices.get(file) {
+            return Some(FileRef::Index(index));
+        }
+        table.indices.insert(*file, table.paths.len() as u32);
+        table.paths.push(*file);
+        Some(FileRef::Path(file.path()))
+    });
+    match file_ref {
+        Some(file_ref) => file_ref.serialize(serializer),
+        None => file.serialize(serializer),
+    }
+}
+
+/// `deserialize_with` for `Span::file`.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FileId, D::Error> {
+    if TABLE.with(|table| table.borrow().is_none()) {
+        return FileId::deserialize(deserializer);
+    }
+    let file_ref = FileRef::<PathBuf>::deserialize(deserializer)?;
+    TABLE.with(|table| {
+        let table = &mut *table.borrow_mut();
+        let table = table.as_mut().unwrap();
+        match file_ref {
+            FileRef::Path(path) => {
+                let file = FileId::from(path);
+                table.paths.push(file);
+                Ok(file)
+            }
+            FileRef::Index(index) => table
+                .paths
+                .get(index as usize)
+                .cloned()
+                .ok_or_else(|| D::Error::custom(format!("unknown file index {index}"))),
+        }
+    })
+}
@@ -0,0 +1,470 @@
+//! A JSON parser that keeps the span of every value and object key.
+
+use crate::{err, Span, Spanned};
+use eyre::Result;
+use std::ops::Range;
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Null(Span),
+    Bool(Spanned<bool>),
+    Number(Spanned<f64>),
+    String(Spanned<String>),
+    Array(Spanned<Vec<Value>>),
+    /// Keys are kept in source order, duplicates included.
+    Object(Spanned<Vec<(Spanned<String>, Value)>>),
+}
+
+impl PartialEq for Value {
+    /// Compares contents and ignores spans, like `Spanned` does.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Null(_), Value::Null(_)) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    pub fn span(&self) -> Span {
+        match self {
+            Value::Null(span) => span.clone(),
+            Value::Bool(b) => b.span(),
+            Value::Number(n) => n.span(),
+            Value::String(s) => s.span(),
+            Value::Array(a) => a.span(),
+            Value::Object(o) => o.span(),
+        }
+    }
+
+    /// `null`, `boolean`, `number`, `string`, `array` or `object`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null(_) => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// The value of the last entry with the given key, if this is an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let Value::Object(entries) = self else {
+            return None;
+        };
+        entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The element at `index`, if this is an array.
+    pub fn index(&self, index: usize) -> Option<&Value> {
+        let Value::Array(elements) = self else {
+            return None;
+        };
+        elements.get(index)
+    }
+
+    /// Look up a value by a JSON pointer like `/dependencies/0/name`.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .try_fold(self, |value, token| match value {
+                Value::Object(_) => value.get(&token),
+                Value::Array(_) => value.index(token.parse().ok()?),
+                _ => None,
+            })
+    }
+
+    pub fn expect_bool(&self) -> Result<Spanned<bool>> {
+        match self {
+            Value::Bool(b) => Ok(b.clone()),
+            _ => Err(self.unexpected("boolean")),
+        }
+    }
+
+    pub fn expect_number(&self) -> Result<Spanned<f64>> {
+        match self {
+            Value::Number(n) => Ok(n.clone()),
+            _ => Err(self.unexpected("number")),
+        }
+    }
+
+    pub fn expect_str(&self) -> Result<Spanned<&str>> {
+        match self {
+            Value::String(s) => Ok(s.as_ref()),
+            _ => Err(self.unexpected("string")),
+        }
+    }
+
+    pub fn expect_array(&self) -> Result<&[Value]> {
+        match self {
+            Value::Array(a) => Ok(a),
+            _ => Err(self.unexpected("array")),
+        }
+    }
+
+    pub fn expect_object(&self) -> Result<&[(Spanned<String>, Value)]> {
+        match self {
+            Value::Object(o) => Ok(o),
+            _ => Err(self.unexpected("object")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> eyre::Report {
+        err!(
+            self.span(),
+            "expected {expected}, found {}",
+            self.type_name()
+        )
+    }
+}
+
+/// Parse a single JSON value, surrounded by optional whitespace.
+pub fn parse(file: Spanned<&str>) -> Result<Value> {
+    let mut parser = Parser {
+        file,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.file.len() {
+        return Err(err!(
+            parser.span(parser.pos..parser.file.len()),
+            "trailing characters"
+        ));
+    }
+    Ok(value)
+}
+
+/// Like serde_json, to not overflow the stack on deeply nested input.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    file: Spanned<&'a str>,
+    pos: usize,
+    /// The number of arrays and objects the parser is currently in.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn span(&self, range: Range<usize>) -> Span {
+        self.file
+            .span
+            .clone()
+            .inc_col_start(range.start)
+            .set_col_end_relative_to_start(range.len())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.file[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.file[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        self.skip_whitespace();
+        if self.peek() != Some(c) {
+            return Err(self.unexpected(&format!("`{c}`")));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn unexpected(&self, expected: &str) -> eyre::Report {
+        match self.peek() {
+            Some(c) => err!(
+                self.span(self.pos..self.pos + c.len_utf8()),
+                "expected {expected}, found `{c}`"
+            ),
+            None => err!(
+                self.span(self.pos..self.pos),
+                "expected {expected}, found end of input"
+            ),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = match self.peek() {
+            Some('{') => return self.object(),
+            Some('[') => return self.array(),
+            Some('"') => return Ok(Value::String(self.string()?)),
+            Some('-' | '0'..='9') => return self.number(),
+            _ => ["null", "true", "false"]
+                .into_iter()
+                .find(|keyword| self.file[start..].starts_with(keyword))
+                .ok_or_else(|| self.unexpected("a value"))?,
+        };
+        self.pos += value.len();
+        let span = self.span(start..self.pos);
+        Ok(match value {
+            "null" => Value::Null(span),
+            _ => Value::Bool(Spanned::new(value == "true", span)),
+        })
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let start = self.pos;
+        let len = self.file[start..]
+            .find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+            .unwrap_or(self.file.len() - start);
+        self.pos += len;
+        let span = self.span(start..self.pos);
+        let n = Some(&self.file[start..self.pos])
+            .filter(|n| is_number(n))
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| err!(span.clone(), "invalid number"))?;
+        Ok(Value::Number(Spanned::new(n, span)))
+    }
+
+    fn string(&mut self) -> Result<Spanned<String>> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut content = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(err!(self.span(start..start + 1), "unterminated string"));
+            };
+            let escape_start = self.pos;
+            self.pos += c.len_utf8();
+            match c {
+                '"' => break,
+                '\\' => {
+                    let Some(escape) = self.peek() else {
+                        continue;
+                    };
+                    self.pos += escape.len_utf8();
+                    content.push(match escape {
+                        '"' | '\\' | '/' => escape,
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.unicode_escape(escape_start)?,
+                        _ => {
+                            return Err(err!(
+                                self.span(escape_start..self.pos),
+                                "unknown escape sequence"
+                            ))
+                        }
+                    });
+                }
+                c if c < ' ' => {
+                    return Err(err!(
+                        self.span(escape_start..self.pos),
+                        "control character in string"
+                    ))
+                }
+                c => content.push(c),
+            }
+        }
+        Ok(Spanned::new(content, self.span(start..self.pos)))
+    }
+
+    /// Parse the hex digits of a `\u` escape, including a following low surrogate.
+    fn unicode_escape(&mut self, escape_start: usize) -> Result<char> {
+        let high = self.hex4();
+        let c = match high {
+            Some(high @ 0xD800..=0xDBFF) if self.file[self.pos..].starts_with("\\u") => {
+                self.pos += 2;
+                self.hex4()
+                    .filter(|low| (0xDC00..=0xDFFF).contains(low))
+                    .and_then(|low| {
+                        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                    })
+            }
+            Some(c) => char::from_u32(c),
+            None => None,
+        };
+        c.ok_or_else(|| err!(self.span(escape_start..self.pos), "invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.file.get(self.pos..self.pos + 4)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let n = u32::from_str_radix(digits, 16).ok()?;
+        self.pos += 4;
+        Some(n)
+    }
+
+    /// Step over the opening bracket of an array or object.
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(err!(
+                self.span(self.pos..self.pos + 1),
+                "nesting too deep, at most {MAX_DEPTH} levels are supported"
+            ));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn array(&mut self) -> Result<Value> {
+        let start = self.pos;
+        self.enter()?;
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+        } else {
+            loop {
+                elements.push(self.value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.unexpected("`,` or `]`")),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::Array(Spanned::new(
+            elements,
+            self.span(start..self.pos),
+        )))
+    }
+
+    fn object(&mut self) -> Result<Value> {
+        let start = self.pos;
+        self.enter()?;
+        let mut entries = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some('"') {
+                    return Err(self.unexpected("a string key"));
+                }
+                let key = self.string()?;
+                self.expect(':')?;
+                entries.push((key, self.value()?));
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some('}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.unexpected("`,` or `}`")),
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::Object(Spanned::new(
+            entries,
+            self.span(start..self.pos),
+        )))
+    }
+}
+
+/// Whether `s` follows the JSON number grammar, which is stricter than `f64::from_str`:
+/// no leading zeros, and digits on both sides of the decimal point.
+fn is_number(s: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let int = digits(s);
+    if int == 0 || (int > 1 && s.starts_with('0')) {
+        return false;
+    }
+    let mut rest = &s[int..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = digits(fraction);
+        if len == 0 {
+            return false;
+        }
+        rest = &fraction[len..];
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let len = digits(exponent);
+        if len == 0 {
+            return false;
+        }
+        rest = &exponent[len..];
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn parse_str(s: &str) -> Result<Value> {
+        let span = Span {
+            file: Path::new("test.json").into(),
+            bytes: 0..s.len(),
+        };
+        parse(Spanned::new(s, span))
+    }
+
+    #[test]
+    fn equality_ignores_spans() {
+        let value = parse_str("[null, null, true, true, [1], [1], null, false]").unwrap();
+        let elements = value.expect_array().unwrap();
+        assert_eq!(elements[0], elements[1]);
+        assert_eq!(elements[2], elements[3]);
+        assert_eq!(elements[4], elements[5]);
+        assert_ne!(elements[6], elements[7]);
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let ok = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(parse_str(&ok).is_ok());
+        let deep = "[".repeat(200_000) + &"]".repeat(200_000);
+        let err = parse_str(&deep).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "test.json:128:129: nesting too deep, at most 128 levels are supported"
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        for valid in ["0", "-0", "1.5", "-12e3", "1E+2", "0.1e-2"] {
+            assert!(parse_str(valid).is_ok(), "{valid}");
+        }
+        for invalid in ["01", "1.", "-", ".5", "1e", "+1", "--1"] {
+            assert!(parse_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        let Value::String(s) = parse_str(r#""\u0041\ud83d\ude00""#).unwrap() else {
+            panic!()
+        };
+        assert_eq!(s.content, "A\u{1F600}");
+        assert!(parse_str(r#""\u+041""#).is_err());
+    }
+}
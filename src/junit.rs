@@ -0,0 +1,50 @@
+use crate::{html::escape, source, ColorChoice, Column, Emitter, Renderer};
+use std::fmt::Write;
+
+impl Emitter {
+    /// Render as a JUnit XML report, see `Renderer::render_junit`.
+    pub fn render_junit(&self, suite: &str) -> String {
+        Renderer::plain().render_junit(self, suite)
+    }
+}
+
+impl Renderer {
+    /// Render all diagnostics of the emitter as a JUnit XML report, with each diagnostic
+    /// being a failed test case. The failure type is the diagnostic's level.
+    /// Paths are remapped like in `render`, colors are never used.
+    pub fn render_junit(&self, emitter: &Emitter, suite: &str) -> String {
+        let plain = self.clone().color_choice(ColorChoice::Never);
+        let diagnostics = emitter.diagnostics();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{1}\" failures=\"{1}\">",
+            escape(suite),
+            diagnostics.len()
+        )
+        .unwrap();
+        for diagnostic in diagnostics {
+            let span = &diagnostic.span;
+            let mut attributes = format!("name=\"{}\"", escape(&diagnostic.message));
+            if !span.is_dummy() {
+                let file = escape(&self.path(&span.file));
+                write!(attributes, " classname=\"{file}\" file=\"{file}\"").unwrap();
+                let pos =
+                    source::with_snapshots(&diagnostic.snapshots, || span.line_col(Column::Char));
+                if let Ok(pos) = pos {
+                    write!(attributes, " line=\"{}\"", pos.line).unwrap();
+                }
+            }
+            writeln!(
+                out,
+                "    <testcase {attributes}>\n      <failure type=\"{}\" message=\"{}\">{}</failure>\n    </testcase>",
+                diagnostic.level,
+                escape(&diagnostic.message),
+                escape(&plain.render(diagnostic)),
+            )
+            .unwrap();
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        out
+    }
+}
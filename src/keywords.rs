@@ -0,0 +1,51 @@
+use crate::{err, Spanned};
+use eyre::Result;
+
+/// Maps a fixed set of keywords to values, typically enum variants.
+/// Unknown keywords produce an error suggesting the closest known keyword.
+#[derive(Clone, Debug)]
+pub struct Keywords<T> {
+    keywords: Vec<(&'static str, T)>,
+}
+
+impl<T> Default for Keywords<T> {
+    fn default() -> Self {
+        Self { keywords: vec![] }
+    }
+}
+
+impl<T: Clone> Keywords<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keyword(mut self, keyword: &'static str, value: T) -> Self {
+        self.keywords.push((keyword, value));
+        self
+    }
+
+    pub fn parse(&self, input: &Spanned<&str>) -> Result<Spanned<T>> {
+        if let Some((_, value)) = self.keywords.iter().find(|(k, _)| *k == input.content) {
+            return Ok(Spanned::new(value.clone(), input.span.clone()));
+        }
+        let expected = self
+            .keywords
+            .iter()
+            .map(|(k, _)| format!("`{k}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let suggestion = crate::suggest::closest(input, self.keywords.iter().map(|(k, _)| *k));
+        match suggestion {
+            Some(k) => Err(err!(
+                input.span.clone(),
+                "unknown keyword `{}`, did you mean `{k}`? Expected one of {expected}",
+                input.content
+            )),
+            None => Err(err!(
+                input.span.clone(),
+                "unknown keyword `{}`, expected one of {expected}",
+                input.content
+            )),
+        }
+    }
+}
@@ -1,3 +1,88 @@
+mod anchored;
+mod balanced;
+#[cfg(feature = "render")]
+mod color;
+mod comments;
+mod context;
+mod continuation;
+pub mod csv;
+mod diagnostic;
+pub mod diff;
+pub mod directives;
+mod edits;
+mod emitter;
+mod env;
+mod exit;
+mod expand;
+mod file;
+mod frontmatter;
+pub mod fs;
+#[cfg(feature = "render")]
+mod github;
+#[cfg(feature = "render")]
+mod html;
+mod indent;
+pub mod ini;
+#[cfg(feature = "serde")]
+mod intern;
+pub mod json;
+#[cfg(feature = "render")]
+mod junit;
+mod keywords;
+mod macros;
+mod mapped;
+#[cfg(feature = "render")]
+mod markdown;
+mod multispan;
+mod ops;
+mod panic;
+mod parse;
+mod position;
+#[cfg(feature = "render")]
+mod render;
+mod shell;
+mod source;
 mod span;
+pub mod suggest;
+#[cfg(feature = "render")]
+pub mod testing;
+#[cfg(feature = "render")]
+mod theme;
+#[cfg(any(feature = "serde_spanned", feature = "toml_edit"))]
+mod toml;
+#[cfg(feature = "tracing")]
+mod trace;
+mod unescape;
+#[cfg(feature = "semver")]
+mod version;
 
+pub use anchored::*;
+#[cfg(feature = "render")]
+pub use color::*;
+pub use context::*;
+pub use diagnostic::*;
+pub use edits::*;
+pub use emitter::*;
+pub use exit::*;
+pub use file::*;
+#[cfg(feature = "serde")]
+pub use intern::*;
+pub use keywords::*;
+pub use mapped::*;
+pub use multispan::*;
+pub use panic::*;
+pub use parse::*;
+pub use position::*;
+#[cfg(feature = "render")]
+pub use render::*;
+pub use source::*;
 pub use span::*;
+#[cfg(feature = "render")]
+pub use theme::*;
+#[cfg(feature = "semver")]
+pub use version::*;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use eyre::eyre;
+}
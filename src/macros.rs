@@ -0,0 +1,67 @@
+/// Create an error pointing at `span` without returning it.
+///
+/// The remaining arguments are a format string and its arguments, like with `format!`.
+#[macro_export]
+macro_rules! err {
+    ($span:expr, $($fmt:tt)+) => {
+        $crate::__private::eyre!("{}: {}", $span, format_args!($($fmt)+))
+    };
+}
+
+/// Return an error pointing at `span` unless `cond` holds.
+///
+/// The remaining arguments are a format string and its arguments, like with `format!`.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $span:expr, $($fmt:tt)+) => {
+        if !$cond {
+            return Err($crate::err!($span, $($fmt)+).into());
+        }
+    };
+}
+
+/// Like `assert!`, but the panic message is a rendered diagnostic pointing at the span of
+/// the given `Spanned` value.
+#[macro_export]
+macro_rules! spanned_assert {
+    ($cond:expr, $spanned:expr $(,)?) => {
+        $crate::spanned_assert!($cond, $spanned, "assertion failed: {}", stringify!($cond))
+    };
+    ($cond:expr, $spanned:expr, $($fmt:tt)+) => {
+        if !$cond {
+            let spanned = &$spanned;
+            let diagnostic = $crate::Diagnostic::new(
+                $crate::Level::Error,
+                format!($($fmt)+),
+                spanned.span.clone(),
+            )
+            .note(format!("content: {:?}", spanned.content));
+            panic!("{diagnostic}");
+        }
+    };
+}
+
+/// Like `assert_eq!`, but compares the content of a `Spanned` value against an expected
+/// value. On failure, the panic message is a rendered diagnostic pointing at the span.
+#[macro_export]
+macro_rules! spanned_assert_eq {
+    ($spanned:expr, $expected:expr $(,)?) => {
+        $crate::spanned_assert_eq!($spanned, $expected, "assertion `left == right` failed")
+    };
+    ($spanned:expr, $expected:expr, $($fmt:tt)+) => {
+        match (&$spanned, &$expected) {
+            (spanned, expected) => {
+                if !(spanned.content == *expected) {
+                    let diagnostic = $crate::Diagnostic::new(
+                        $crate::Level::Error,
+                        format!($($fmt)+),
+                        spanned.span.clone(),
+                    )
+                    .note(format!(" left: {:?}", spanned.content))
+                    .note(format!("right: {:?}", expected));
+                    panic!("{diagnostic}");
+                }
+            }
+        }
+    };
+}
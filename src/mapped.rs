@@ -0,0 +1,45 @@
+use crate::{Span, Spanned};
+use std::ops::Range;
+
+/// A string built from pieces of spanned input, e.g. by expanding variables,
+/// that remembers where each part of it came from.
+#[derive(Clone, Debug)]
+pub struct Mapped {
+    pub content: Spanned<String>,
+    pub segments: Vec<Segment>,
+}
+
+/// A range of the output and the span of the input it came from.
+#[derive(Clone, Debug)]
+pub struct Segment {
+    pub output: Range<usize>,
+    pub span: Span,
+    /// Literal text maps byte for byte, anything else (e.g. an expanded variable)
+    /// maps to its entire span.
+    pub literal: bool,
+}
+
+impl Mapped {
+    /// The input span that produced the given range of the output.
+    pub fn span_of(&self, range: Range<usize>) -> Span {
+        let mut result = Span::default();
+        for segment in &self.segments {
+            let start = range.start.max(segment.output.start);
+            let end = range.end.min(segment.output.end);
+            if start > end || (start == end && !range.is_empty()) {
+                continue;
+            }
+            let span = if segment.literal {
+                segment
+                    .span
+                    .clone()
+                    .inc_col_start(start - segment.output.start)
+                    .set_col_end_relative_to_start(end - start)
+            } else {
+                segment.span.clone()
+            };
+            result = result.join(&span);
+        }
+        result
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{source, Diagnostic, Level, Renderer, Span};
+use std::fmt::Write;
+use unicode_width::UnicodeWidthStr;
+
+impl Diagnostic {
+    /// Render as Markdown, see `Renderer::render_markdown`.
+    pub fn render_markdown(&self) -> String {
+        Renderer::plain().render_markdown(self)
+    }
+}
+
+impl Renderer {
+    /// Render as Markdown, e.g. for a pull request comment: a `file:line:col` heading,
+    /// the message in bold and the source snippet in a fenced code block.
+    /// Paths are remapped like in `render`.
+    pub fn render_markdown(&self, diagnostic: &Diagnostic) -> String {
+        source::with_snapshots(&diagnostic.snapshots, || {
+            let mut out = String::new();
+            message(
+                self,
+                &mut out,
+                diagnostic.level,
+                &diagnostic.message,
+                &diagnostic.span,
+            );
+            if let Some(label) = &diagnostic.primary_label {
+                writeln!(out, "\n{}", escape(label)).unwrap();
+            }
+            for label in &diagnostic.labels {
+                out.push('\n');
+                message(self, &mut out, label.level, &label.message, &label.span);
+            }
+            if !diagnostic.footers.is_empty() {
+                out.push('\n');
+                for footer in &diagnostic.footers {
+                    write!(out, "- **{}**: {}", footer.level, escape(&footer.message)).unwrap();
+                    if let Some(url) = &footer.url {
+                        write!(out, " <{url}>").unwrap();
+                    }
+                    out.push('\n');
+                }
+            }
+            out
+        })
+    }
+}
+
+fn message(renderer: &Renderer, out: &mut String, level: Level, message: &str, span: &Span) {
+    if !span.is_dummy() {
+        writeln!(out, "#### `{}`\n", renderer.location(span)).unwrap();
+    }
+    writeln!(out, "**{level}: {}**", escape(message)).unwrap();
+    let Ok(snippet) = span.snippet() else {
+        return;
+    };
+    let text = &snippet.text;
+    let highlight = snippet.highlight;
+    let longest_backticks = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_backticks.max(2) + 1);
+    writeln!(out, "\n{fence}text").unwrap();
+    let line_start = text[..highlight.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[highlight.start..]
+        .find('\n')
+        .map_or(text.len(), |i| highlight.start + i);
+    for line in text.lines() {
+        writeln!(out, "{line}").unwrap();
+        // Only underline spans that are on a single line.
+        let is_highlighted_line = line.as_ptr() == text[line_start..].as_ptr();
+        if is_highlighted_line && highlight.end <= line_end {
+            let indent = text[line_start..highlight.start].width();
+            let len = text[highlight.clone()].width().max(1);
+            writeln!(out, "{}{}", " ".repeat(indent), "^".repeat(len)).unwrap();
+        }
+    }
+    writeln!(out, "{fence}").unwrap();
+}
+
+/// Escape everything Markdown could interpret as formatting, and turn newlines into
+/// `<br>` so they don't end the paragraph.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '{' | '}' | '[' | ']' | '<' | '>' | '(' | ')' | '#' | '+'
+            | '-' | '.' | '!' | '|' | '~' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Diagnostic;
+
+    #[test]
+    fn escapes_messages() {
+        let diagnostic = Diagnostic::error("a *b* _c_\nd")
+            .help("use `x`")
+            .note("[link](y)");
+        assert_eq!(
+            diagnostic.render_markdown(),
+            "**error: a \\*b\\* \\_c\\_<br>d**\n\n- **help**: use \\`x\\`\n- **note**: \\[link\\]\\(y\\)\n"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{Diagnostic, Level, Span};
+
+/// A primary span plus any number of labelled secondary spans, possibly in
+/// different files. E.g. a duplicate key and all of its previous definitions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultiSpan {
+    pub primary: Span,
+    pub secondary: Vec<(Span, String)>,
+}
+
+impl MultiSpan {
+    pub fn new(primary: Span) -> Self {
+        Self {
+            primary,
+            secondary: vec![],
+        }
+    }
+
+    pub fn secondary(mut self, span: Span, label: impl Into<String>) -> Self {
+        self.secondary.push((span, label.into()));
+        self
+    }
+
+    /// The primary span followed by all secondary spans.
+    pub fn spans(&self) -> impl Iterator<Item = &Span> {
+        std::iter::once(&self.primary).chain(self.secondary.iter().map(|(span, _)| span))
+    }
+}
+
+impl From<Span> for MultiSpan {
+    fn from(primary: Span) -> Self {
+        Self::new(primary)
+    }
+}
+
+impl Diagnostic {
+    /// A diagnostic pointing at the primary span, with a note label for each secondary span.
+    pub fn multi(level: Level, message: impl Into<String>, spans: impl Into<MultiSpan>) -> Self {
+        let spans = spans.into();
+        spans.secondary.into_iter().fold(
+            Self::new(level, message, spans.primary),
+            |diag, (span, label)| diag.span_note(span, label),
+        )
+    }
+}
@@ -0,0 +1,99 @@
+//! Operators on `Spanned` values. Arithmetic produces a value spanning both operands,
+//! comparisons only look at the content.
+
+use crate::Spanned;
+use std::{cmp::Ordering, ops};
+
+macro_rules! binop {
+    ($($trait:ident $method:ident)*) => {$(
+        impl<T: ops::$trait<U>, U> ops::$trait<Spanned<U>> for Spanned<T> {
+            type Output = Spanned<T::Output>;
+
+            fn $method(self, rhs: Spanned<U>) -> Self::Output {
+                Spanned {
+                    span: self.span.join(&rhs.span),
+                    content: self.content.$method(rhs.content),
+                }
+            }
+        }
+    )*};
+}
+
+binop! {
+    Add add
+    Sub sub
+    Mul mul
+    Div div
+    Rem rem
+    BitAnd bitand
+    BitOr bitor
+    BitXor bitxor
+    Shl shl
+    Shr shr
+}
+
+macro_rules! unop {
+    ($($trait:ident $method:ident)*) => {$(
+        impl<T: ops::$trait> ops::$trait for Spanned<T> {
+            type Output = Spanned<T::Output>;
+
+            fn $method(self) -> Self::Output {
+                self.map(ops::$trait::$method)
+            }
+        }
+    )*};
+}
+
+unop! {
+    Neg neg
+    Not not
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.content == other.content
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: PartialOrd> PartialOrd for Spanned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.content.partial_cmp(&other.content)
+    }
+}
+
+impl<T: Ord> Ord for Spanned<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.content.cmp(&other.content)
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for Spanned<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.content == *other
+    }
+}
+
+macro_rules! str_eq {
+    ($($lhs:ty, $rhs:ty;)*) => {$(
+        impl PartialEq<$rhs> for Spanned<$lhs> {
+            fn eq(&self, other: &$rhs) -> bool {
+                self.content[..] == other[..]
+            }
+        }
+
+        impl PartialEq<Spanned<$lhs>> for $rhs {
+            fn eq(&self, other: &Spanned<$lhs>) -> bool {
+                self[..] == other.content[..]
+            }
+        }
+    )*};
+}
+
+str_eq! {
+    &str, str;
+    &str, String;
+    String, str;
+    String, &str;
+}
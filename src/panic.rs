@@ -0,0 +1,58 @@
+use crate::{Diagnostic, Level, Span, Spanned};
+use std::{any::Any, cell::RefCell, panic::PanicHookInfo};
+
+thread_local! {
+    static CURRENT_SPAN: RefCell<Option<Span>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `span` as the current span of this thread, which the panic hook
+/// installed by `install_panic_hook` points at if `f` panics.
+pub fn with_current_span<R>(span: Span, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Span>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_SPAN.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT_SPAN.with(|current| current.replace(Some(span))));
+    f()
+}
+
+/// Replace the panic hook with one that renders a diagnostic if the panic payload
+/// is a `Diagnostic`, a `Spanned<String>` or a `Spanned<&str>`, or if a current span
+/// was set with `with_current_span`. Other panics go to the previous hook.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| match diagnostic(info) {
+        Some(diagnostic) => eprintln!("{}", diagnostic.render_auto()),
+        None => previous(info),
+    }));
+}
+
+fn diagnostic(info: &PanicHookInfo<'_>) -> Option<Diagnostic> {
+    let payload = info.payload();
+    let diagnostic = if let Some(diagnostic) = payload.downcast_ref::<Diagnostic>() {
+        diagnostic.clone()
+    } else if let Some(spanned) = payload.downcast_ref::<Spanned<String>>() {
+        Diagnostic::new(Level::Error, spanned.content.clone(), spanned.span.clone())
+    } else if let Some(spanned) = payload.downcast_ref::<Spanned<&str>>() {
+        Diagnostic::new(Level::Error, spanned.content, spanned.span.clone())
+    } else {
+        let span = CURRENT_SPAN.with(|current| current.borrow().clone())?;
+        Diagnostic::new(Level::Error, message(payload), span)
+    };
+    Some(match info.location() {
+        Some(location) => diagnostic.note(format!("panicked at {location}")),
+        None => diagnostic,
+    })
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".into()
+    }
+}
@@ -0,0 +1,124 @@
+use crate::Spanned;
+use eyre::{Context, Report, Result};
+use std::{num::ParseIntError, ops::Range, str::FromStr};
+
+/// Integers that can be parsed in an arbitrary radix.
+pub trait FromStrRadix: Sized {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! from_str_radix {
+    ($($t:ty)*) => {$(
+        impl FromStrRadix for $t {
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$t>::from_str_radix(s, radix)
+            }
+        }
+    )*};
+}
+
+from_str_radix!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+/// Numbers whose parse errors can be narrowed down to the offending characters.
+pub trait PreciseFromStr: FromStr {
+    /// The byte range that made parsing `s` fail.
+    fn error_range(s: &str) -> Range<usize>;
+}
+
+macro_rules! precise_int {
+    ($($t:ty)*) => {$(
+        impl PreciseFromStr for $t {
+            fn error_range(s: &str) -> Range<usize> {
+                invalid_digit(s, 10)
+            }
+        }
+    )*};
+}
+
+precise_int!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+impl PreciseFromStr for f32 {
+    fn error_range(s: &str) -> Range<usize> {
+        invalid_float_char(s)
+    }
+}
+
+impl PreciseFromStr for f64 {
+    fn error_range(s: &str) -> Range<usize> {
+        invalid_float_char(s)
+    }
+}
+
+impl Spanned<&str> {
+    /// Parse with a custom function. On failure, the function returns the byte range
+    /// within the content that caused the error, and the error span is narrowed to it.
+    /// If the range is not within the content, the error says so instead.
+    pub fn parse_with<T, E>(
+        self,
+        f: impl FnOnce(&str) -> Result<T, (Range<usize>, E)>,
+    ) -> Result<Spanned<T>>
+    where
+        E: Into<Report>,
+    {
+        match f(self.content) {
+            Ok(content) => Ok(Spanned {
+                span: self.span,
+                content,
+            }),
+            Err((range, err)) => {
+                let span = self
+                    .span
+                    .clone()
+                    .try_inc_col_start(range.start)
+                    .and_then(|span| span.try_set_col_end_relative_to_start(range.len()));
+                match span {
+                    Ok(span) if range.start <= range.end => Err(err.into()).with_context(|| span),
+                    _ => Err(err.into()).with_context(|| {
+                        format!("{}: error range {range:?} is out of bounds", self.span)
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Parse an integer in the given radix. If there is an invalid digit, the
+    /// error points at it instead of the whole content.
+    pub fn parse_int_radix<T: FromStrRadix>(self, radix: u32) -> Result<Spanned<T>> {
+        self.parse_with(|s| {
+            T::from_str_radix(s, radix).map_err(|err| (invalid_digit(s, radix), err))
+        })
+    }
+
+    /// Like `parse`, but the error points at the first invalid character instead of the
+    /// whole content.
+    pub fn parse_precise<T: PreciseFromStr>(self) -> Result<Spanned<T>>
+    where
+        T::Err: Into<Report>,
+    {
+        self.parse_with(|s| s.parse().map_err(|err| (T::error_range(s), err)))
+    }
+}
+
+/// The range of the first character that is not a digit, or the entire string if
+/// all the digits are fine (e.g. because the number overflowed).
+fn invalid_digit(s: &str, radix: u32) -> Range<usize> {
+    let sign = usize::from(s.starts_with(['+', '-']));
+    s[sign..]
+        .char_indices()
+        .find(|(_, c)| !c.is_digit(radix))
+        .map(|(i, c)| sign + i..sign + i + c.len_utf8())
+        .unwrap_or(0..s.len())
+}
+
+/// The range of the first character that cannot occur in a float, or the entire string
+/// if the characters are fine but their order is not.
+fn invalid_float_char(s: &str) -> Range<usize> {
+    let lower = s.trim_start_matches(['+', '-']).to_ascii_lowercase();
+    if ["inf", "infinity", "nan"].contains(&lower.as_str()) {
+        return 0..s.len();
+    }
+    s.char_indices()
+        .find(|&(_, c)| !matches!(c, '0'..='9' | '+' | '-' | '.' | 'e' | 'E'))
+        .map(|(i, c)| i..i + c.len_utf8())
+        .unwrap_or(0..s.len())
+}
@@ -0,0 +1,214 @@
+use crate::{source, Span, Spanned};
+use bstr::ByteSlice;
+use eyre::{eyre, Result};
+use std::{fmt::Display, path::PathBuf};
+#[cfg(feature = "render")]
+use unicode_width::UnicodeWidthChar;
+
+/// How to count columns within a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// Bytes since the start of the line.
+    #[default]
+    Byte,
+    /// Unicode scalar values since the start of the line.
+    Char,
+    /// UTF-16 code units since the start of the line, as used by LSP.
+    Utf16,
+    /// Terminal cells since the start of the line. Tabs count as four cells.
+    #[cfg(feature = "render")]
+    DisplayWidth,
+}
+
+/// A 1-based line and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for LineCol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl Column {
+    fn measure(self, text: &[u8]) -> usize {
+        match self {
+            Column::Byte => text.len(),
+            Column::Char => text.chars().count(),
+            Column::Utf16 => text.chars().map(char::len_utf16).sum(),
+            #[cfg(feature = "render")]
+            Column::DisplayWidth => text
+                .chars()
+                .map(|c| match c {
+                    '\t' => 4,
+                    c => c.width().unwrap_or(0),
+                })
+                .sum(),
+        }
+    }
+}
+
+fn line_col_at(
+    source: &[u8],
+    line_starts: &[usize],
+    offset: usize,
+    column: Column,
+) -> Option<LineCol> {
+    let before = source.get(..offset)?;
+    let line = line_starts.partition_point(|&start| start <= offset);
+    Some(LineCol {
+        line,
+        column: column.measure(&before[line_starts[line - 1]..]) + 1,
+    })
+}
+
+/// The full lines a span covers, decoded lossily.
+#[cfg(feature = "render")]
+pub(crate) struct Snippet {
+    pub text: String,
+    /// The part of `text` covered by the span.
+    pub highlight: std::ops::Range<usize>,
+}
+
+impl Span {
+    #[cfg(feature = "render")]
+    pub(crate) fn snippet(&self) -> Result<Snippet> {
+        if self.is_dummy() {
+            return Err(eyre!("DUMMY_SPAN has no snippet"));
+        }
+        let source = source::read(&self.file)?;
+        if self.bytes.start > self.bytes.end || self.bytes.end > source.len() {
+            return Err(eyre!("{self} is out of bounds"));
+        }
+        let start = source[..self.bytes.start]
+            .rfind_byte(b'\n')
+            .map_or(0, |i| i + 1);
+        let end = source[self.bytes.end..]
+            .find_byte(b'\n')
+            .map_or(source.len(), |i| self.bytes.end + i);
+        let before = source[start..self.bytes.start].to_str_lossy();
+        let inner = source[self.bytes.clone()].to_str_lossy();
+        let after = source[self.bytes.end..end].to_str_lossy();
+        let after = after.strip_suffix('\r').unwrap_or(&after);
+        Ok(Snippet {
+            text: format!("{before}{inner}{after}"),
+            highlight: before.len()..before.len() + inner.len(),
+        })
+    }
+
+    /// The full lines covered by the span, plus up to `lines_before` and `lines_after`
+    /// lines of context. The span of the result covers exactly the returned lines,
+    /// excluding the final line terminator. Invalid UTF-8 is replaced.
+    pub fn snippet_with_context(
+        &self,
+        lines_before: usize,
+        lines_after: usize,
+    ) -> Result<Spanned<String>> {
+        if self.is_dummy() {
+            return Err(eyre!("DUMMY_SPAN has no snippet"));
+        }
+        let source = source::load(&self.file)?;
+        let (source, line_starts) = (&source.content, source.line_starts());
+        if self.bytes.start > self.bytes.end || self.bytes.end > source.len() {
+            return Err(eyre!("{self} is out of bounds"));
+        }
+        let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+        let first = line_of(self.bytes.start);
+        // A span ending right after a newline does not cover the next line.
+        let last = line_of(self.bytes.end.max(self.bytes.start + 1) - 1);
+        let first = first.saturating_sub(lines_before);
+        // A trailing newline does not start another line.
+        let last_line = line_starts.len() - 1 - usize::from(source.last() == Some(&b'\n'));
+        let last = (last + lines_after).min(last_line.max(last));
+        let start = line_starts[first];
+        let mut end = line_starts
+            .get(last + 1)
+            .map_or(source.len(), |&next| next - 1);
+        if end > start && source[end - 1] == b'\r' {
+            end -= 1;
+        }
+        let content = source[start..end].to_str_lossy().into_owned();
+        let span = Span {
+            file: self.file,
+            bytes: start..end,
+        };
+        Ok(Spanned::new(content, span))
+    }
+
+    /// A span covering the 1-based `line` of `path`, excluding the line terminator.
+    /// Useful for diagnostics from tools that only report line numbers.
+    pub fn line(path: impl Into<PathBuf>, line: usize) -> Result<Span> {
+        let file = path.into();
+        let source = source::load(&file)?;
+        let (source, line_starts) = (&source.content, source.line_starts());
+        let start = line
+            .checked_sub(1)
+            .and_then(|i| line_starts.get(i).copied());
+        let Some(start) = start.filter(|&start| start < source.len() || line == 1) else {
+            return Err(eyre!("{} has no line {line}", file.display()));
+        };
+        let mut end = line_starts.get(line).map_or(source.len(), |&next| next - 1);
+        if end > start && source[end - 1] == b'\r' {
+            end -= 1;
+        }
+        Ok(Span {
+            file: file.into(),
+            bytes: start..end,
+        })
+    }
+
+    /// The line and column of the start of the span. This reads the file.
+    pub fn line_col(&self, column: Column) -> Result<LineCol> {
+        self.line_col_at(self.bytes.start, column)
+    }
+
+    /// The line and column of the end of the span. This reads the file.
+    pub fn end_line_col(&self, column: Column) -> Result<LineCol> {
+        self.line_col_at(self.bytes.end, column)
+    }
+
+    /// The line and UTF-16 column of the start of the span, as used by LSP and many editors.
+    /// Note that both are 1-based, while LSP positions are 0-based.
+    pub fn line_col_utf16(&self) -> Result<LineCol> {
+        self.line_col(Column::Utf16)
+    }
+
+    fn line_col_at(&self, offset: usize, column: Column) -> Result<LineCol> {
+        if self.is_dummy() {
+            return Err(eyre!("DUMMY_SPAN has no line or column"));
+        }
+        let source = source::load(&self.file)?;
+        let (source, line_starts) = (&source.content, source.line_starts());
+        line_col_at(source, line_starts, offset, column)
+            .ok_or_else(|| eyre!("{self} is out of bounds"))
+    }
+
+    /// Display as `file:line:col` instead of byte offsets. Falls back to
+    /// `file:bytes start..end` if the file cannot be read or the span is out of bounds.
+    pub fn display_line_col(&self, column: Column) -> DisplayLineCol<'_> {
+        DisplayLineCol { span: self, column }
+    }
+}
+
+pub struct DisplayLineCol<'a> {
+    span: &'a Span,
+    column: Column,
+}
+
+impl Display for DisplayLineCol<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.span.line_col(self.column) {
+            Ok(pos) => write!(f, "{}:{pos}", self.span.file.display()),
+            Err(_) => write!(
+                f,
+                "{}:bytes {}..{}",
+                self.span.file.display(),
+                self.span.bytes.start,
+                self.span.bytes.end
+            ),
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use crate::{color_choice, source, ColorChoice, Column, Diagnostic, Level, Span, Style, Theme};
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthChar;
+
+/// Turns diagnostics into strings, with explicit control over colors.
+#[derive(Clone, Debug, Default)]
+pub struct Renderer {
+    color: bool,
+    theme: Theme,
+    hyperlinks: Option<String>,
+    remap_path_prefix: Vec<(PathBuf, PathBuf)>,
+    forward_slashes: bool,
+}
+
+impl Renderer {
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    pub fn colored() -> Self {
+        Self {
+            color: true,
+            ..Self::default()
+        }
+    }
+
+    /// Color according to the global `set_color_choice`, for output written to stderr.
+    pub fn auto() -> Self {
+        Self::default().color_choice(color_choice())
+    }
+
+    /// Override whether to color, independent of the global choice.
+    pub fn color_choice(mut self, choice: ColorChoice) -> Self {
+        self.color = choice.should_color();
+        self
+    }
+
+    /// Use the given styles when coloring output.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Output that is the same on every machine and OS, for golden tests:
+    /// no colors and `/` as the path separator. Combine with `strip_path_prefix`
+    /// to get rid of machine specific directories.
+    pub fn deterministic() -> Self {
+        Self {
+            forward_slashes: true,
+            ..Self::default()
+        }
+    }
+
+    /// Show paths relative to `prefix` if they start with it.
+    pub fn strip_path_prefix(self, prefix: impl Into<PathBuf>) -> Self {
+        self.remap_path_prefix(prefix, "")
+    }
+
+    /// Show paths starting with `from` as starting with `to` instead, like rustc's
+    /// `--remap-path-prefix`. If several prefixes match, the one added last wins.
+    pub fn remap_path_prefix(mut self, from: impl Into<PathBuf>, to: impl Into<PathBuf>) -> Self {
+        self.remap_path_prefix.push((from.into(), to.into()));
+        self
+    }
+
+    /// Print `/` as the path separator, even on Windows.
+    pub fn forward_slashes(mut self) -> Self {
+        self.forward_slashes = true;
+        self
+    }
+
+    /// Turn file paths into OSC-8 terminal hyperlinks. The link target is the `scheme`
+    /// followed by the absolute path of the file, e.g. `file://` or `vscode://file`.
+    pub fn hyperlinks(mut self, scheme: impl Into<String>) -> Self {
+        self.hyperlinks = Some(scheme.into());
+        self
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        source::with_snapshots(&diagnostic.snapshots, || {
+            let mut out = String::new();
+            self.message(
+                &mut out,
+                diagnostic.level,
+                &diagnostic.message,
+                &diagnostic.span,
+                diagnostic.primary_label.as_deref(),
+            );
+            for label in &diagnostic.labels {
+                out.push('\n');
+                self.message(&mut out, label.level, &label.message, &label.span, None);
+            }
+            for footer in &diagnostic.footers {
+                out.push_str("\n  = ");
+                self.paint(
+                    &mut out,
+                    self.theme.level(footer.level),
+                    &footer.level.to_string(),
+                );
+                out.push_str(": ");
+                out.push_str(&footer.message);
+                if let Some(url) = &footer.url {
+                    out.push_str("\n    ");
+                    self.paint(&mut out, self.theme.arrow, "see");
+                    out.push(' ');
+                    if self.hyperlinks.is_some() {
+                        out.push_str(&format!("\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\"));
+                    } else {
+                        out.push_str(url);
+                    }
+                }
+            }
+            out
+        })
+    }
+
+    fn message(
+        &self,
+        out: &mut String,
+        level: Level,
+        message: &str,
+        span: &Span,
+        label: Option<&str>,
+    ) {
+        self.paint(out, self.theme.level(level), &level.to_string());
+        self.paint(out, self.theme.message, &format!(": {message}"));
+        if !span.is_dummy() {
+            out.push_str("\n  ");
+            self.paint(out, self.theme.arrow, "-->");
+            out.push(' ');
+            self.link(out, span);
+        }
+        if !self.snippet(out, level, span, label) {
+            if let Some(label) = label {
+                self.gutter(out, " ");
+                out.push(' ');
+                out.push_str(label);
+            }
+        }
+    }
+
+    /// A line of the source snippet's left margin, without a line number.
+    fn gutter(&self, out: &mut String, gutter: &str) {
+        out.push_str(&format!("\n{gutter} "));
+        self.paint(out, self.theme.arrow, "|");
+    }
+
+    /// The source lines of the span, with the span underlined if it is on a single line.
+    /// Spans over more than three lines only show their first and last line.
+    /// Returns `false` if the source could not be read, so nothing was printed.
+    fn snippet(&self, out: &mut String, level: Level, span: &Span, label: Option<&str>) -> bool {
+        let (Ok(mut snippet), Ok(pos)) = (span.snippet(), span.line_col(Column::Char)) else {
+            return false;
+        };
+        // A span ending with a newline does not cover the next line.
+        let inner = &snippet.text[snippet.highlight.clone()];
+        if let Some(trimmed) = inner.strip_suffix('\n') {
+            let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+            let mut span = span.clone();
+            span.bytes.end -= inner.len() - trimmed.len();
+            match span.snippet() {
+                Ok(trimmed) => snippet = trimmed,
+                Err(_) => return false,
+            }
+        }
+        let text = &snippet.text;
+        let highlight = snippet.highlight;
+        let lines: Vec<&str> = text.split('\n').collect();
+        let last_line = pos.line + lines.len() - 1;
+        let gutter = " ".repeat(last_line.to_string().len());
+        self.gutter(out, &gutter);
+        let numbered_line = |out: &mut String, i: usize| {
+            out.push('\n');
+            self.paint(
+                out,
+                self.theme.arrow,
+                &format!("{:>1$} |", pos.line + i, gutter.len()),
+            );
+            let line = lines[i].strip_suffix('\r').unwrap_or(lines[i]);
+            if !line.is_empty() {
+                out.push(' ');
+                out.push_str(&line.replace('\t', "    "));
+            }
+        };
+        if lines.len() > 1 {
+            if lines.len() > 3 {
+                numbered_line(out, 0);
+                out.push('\n');
+                self.paint(out, self.theme.arrow, "...");
+                numbered_line(out, lines.len() - 1);
+            } else {
+                for i in 0..lines.len() {
+                    numbered_line(out, i);
+                }
+            }
+            if let Some(label) = label {
+                self.gutter(out, &gutter);
+                out.push(' ');
+                self.paint(out, self.theme.level(level), label);
+            }
+            return true;
+        }
+        numbered_line(out, 0);
+        self.gutter(out, &gutter);
+        out.push(' ');
+        out.push_str(&" ".repeat(width(&text[..highlight.start])));
+        let carets = "^".repeat(width(&text[highlight]).max(1));
+        self.paint(out, self.theme.level(level), &carets);
+        if let Some(label) = label {
+            out.push(' ');
+            self.paint(out, self.theme.level(level), label);
+        }
+        true
+    }
+
+    /// `path:line:column`, with the path remapped as configured.
+    /// Falls back to the byte range if the file can't be read.
+    pub(crate) fn location(&self, span: &Span) -> String {
+        let path = self.path(&span.file);
+        match span.line_col(Column::Char) {
+            Ok(pos) => format!("{path}:{pos}"),
+            Err(_) => format!("{path}:bytes {}..{}", span.bytes.start, span.bytes.end),
+        }
+    }
+
+    fn link(&self, out: &mut String, span: &Span) {
+        let text = self.location(span);
+        let Some(scheme) = &self.hyperlinks else {
+            out.push_str(&text);
+            return;
+        };
+        let path = std::path::absolute(span.file).unwrap_or_else(|_| span.file.to_path_buf());
+        let url = format!("{scheme}{}", path.display());
+        out.push_str(&format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\"));
+    }
+
+    pub(crate) fn path(&self, path: &Path) -> String {
+        let path = self
+            .remap_path_prefix
+            .iter()
+            .rev()
+            .find_map(|(from, to)| {
+                let rest = path.strip_prefix(from).ok()?;
+                // `join` would append a trailing separator for an empty `rest`.
+                Some(if rest.as_os_str().is_empty() {
+                    to.clone()
+                } else {
+                    to.join(rest)
+                })
+            })
+            .unwrap_or_else(|| path.to_path_buf());
+        let path = path.display().to_string();
+        if self.forward_slashes {
+            path.replace('\\', "/")
+        } else {
+            path
+        }
+    }
+
+    fn paint(&self, out: &mut String, style: Style, text: &str) {
+        let ansi = style.ansi();
+        if self.color && !ansi.is_empty() {
+            out.push_str(&ansi);
+            out.push_str(text);
+            out.push_str("\x1b[0m");
+        } else {
+            out.push_str(text);
+        }
+    }
+}
+
+/// Terminal cells, with tabs counting as four like `Column::DisplayWidth`.
+fn width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c {
+            '\t' => 4,
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_source;
+
+    fn span(file: &str, bytes: std::ops::Range<usize>) -> Span {
+        Span {
+            file: Path::new(file).into(),
+            bytes,
+        }
+    }
+
+    #[test]
+    fn missing_file() {
+        let file = "/nonexistent/spanned/missing.rs";
+        let diagnostic = Diagnostic::new(Level::Error, "gone", span(file, 3..5));
+        assert_eq!(
+            Renderer::plain().render(&diagnostic),
+            format!("error: gone\n  --> {file}:bytes 3..5")
+        );
+    }
+
+    #[test]
+    fn binary_file() {
+        let file = "<render test binary>";
+        register_source(file, &b"\x00\xff\xfe abc\n"[..]);
+        let diagnostic = Diagnostic::new(Level::Error, "binary", span(file, 4..7));
+        let rendered = Renderer::plain().render(&diagnostic);
+        assert!(rendered.starts_with(&format!("error: binary\n  --> {file}:1:5\n")));
+        assert!(rendered.contains("\u{fffd}\u{fffd} abc"), "{rendered}");
+        assert!(rendered.ends_with("^^^"), "{rendered}");
+    }
+
+    #[test]
+    fn dummy_span() {
+        let diagnostic = Diagnostic::error("nowhere")
+            .secondary(Span::default(), "also nowhere")
+            .help("try somewhere");
+        assert_eq!(
+            Renderer::plain().render(&diagnostic),
+            "error: nowhere\nnote: also nowhere\n  = help: try somewhere"
+        );
+    }
+
+    fn render(file: &str, content: &str, bytes: std::ops::Range<usize>, label: &str) -> String {
+        register_source(file, content.as_bytes());
+        let diagnostic = Diagnostic::error("oops").primary(span(file, bytes), label);
+        Renderer::plain().render(&diagnostic)
+    }
+
+    #[test]
+    fn long_span_is_folded() {
+        let content = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        let rendered = render("<render test fold>", content, 0..content.len(), "here");
+        assert_eq!(
+            rendered,
+            "error: oops\n  --> <render test fold>:1:1\n  |\n1 | 1\n...\n8 | 8\n  | here"
+        );
+    }
+
+    #[test]
+    fn short_multi_line_span() {
+        let rendered = render("<render test lines>", "ab\ncd\nef", 1..4, "here");
+        assert_eq!(
+            rendered,
+            "error: oops\n  --> <render test lines>:1:2\n  |\n1 | ab\n2 | cd\n  | here"
+        );
+    }
+
+    #[test]
+    fn trailing_newline_is_not_another_line() {
+        let rendered = render("<render test newline>", "ab\r\ncd\n", 0..4, "here");
+        assert_eq!(
+            rendered,
+            "error: oops\n  --> <render test newline>:1:1\n  |\n1 | ab\n  | ^^ here"
+        );
+    }
+
+    #[test]
+    fn tabs_are_expanded() {
+        let rendered = render("<render test tabs>", "\tx = 1", 1..2, "here");
+        assert_eq!(
+            rendered,
+            "error: oops\n  --> <render test tabs>:1:2\n  |\n1 |     x = 1\n  |     ^ here"
+        );
+    }
+
+    #[test]
+    fn label_without_snippet() {
+        let file = "/nonexistent/spanned/label.rs";
+        let diagnostic = Diagnostic::error("gone").primary(span(file, 3..5), "here");
+        assert_eq!(
+            Renderer::plain().render(&diagnostic),
+            format!("error: gone\n  --> {file}:bytes 3..5\n  | here")
+        );
+    }
+
+    #[test]
+    fn remap_path_prefix() {
+        let renderer = Renderer::plain().remap_path_prefix("/work", "y");
+        assert_eq!(renderer.path(Path::new("/work/x.txt")), "y/x.txt");
+        assert_eq!(renderer.path(Path::new("/other/x.txt")), "/other/x.txt");
+        let renderer = Renderer::plain().remap_path_prefix("/work/x.txt", "y");
+        assert_eq!(renderer.path(Path::new("/work/x.txt")), "y");
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{err, Spanned};
+use eyre::Result;
+
+impl Spanned<&str> {
+    /// Split into arguments like a POSIX shell would, handling single quotes, double
+    /// quotes and backslash escapes. Each argument's span covers its quoted form.
+    pub fn split_shell_words(&self) -> Result<Vec<Spanned<String>>> {
+        let mut words = vec![];
+        let mut chars = self.content.char_indices().peekable();
+        let span_of = |start: usize, end: usize| {
+            self.span
+                .clone()
+                .inc_col_start(start)
+                .set_col_end_relative_to_start(end - start)
+        };
+        while let Some(&(start, _)) = chars.peek() {
+            let mut word = String::new();
+            let mut end = start;
+            while let Some((i, c)) = chars.next() {
+                end = i + c.len_utf8();
+                match c {
+                    c if c.is_whitespace() => {
+                        end = i;
+                        break;
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, '\n')) => {}
+                        Some((j, c)) => {
+                            word.push(c);
+                            end = j + c.len_utf8();
+                        }
+                        None => return Err(err!(span_of(i, end), "trailing backslash")),
+                    },
+                    '\'' => loop {
+                        match chars.next() {
+                            Some((j, '\'')) => {
+                                end = j + 1;
+                                break;
+                            }
+                            Some((_, c)) => word.push(c),
+                            None => {
+                                return Err(err!(span_of(i, i + 1), "unterminated single quote"))
+                            }
+                        }
+                    },
+                    '"' => loop {
+                        match chars.next() {
+                            Some((j, '"')) => {
+                                end = j + 1;
+                                break;
+                            }
+                            Some((_, '\\')) => match chars
+                                .next_if(|&(_, c)| matches!(c, '$' | '`' | '"' | '\\' | '\n'))
+                            {
+                                Some((_, '\n')) => {}
+                                Some((_, c)) => word.push(c),
+                                None => word.push('\\'),
+                            },
+                            Some((_, c)) => word.push(c),
+                            None => {
+                                return Err(err!(span_of(i, i + 1), "unterminated double quote"))
+                            }
+                        }
+                    },
+                    c => word.push(c),
+                }
+            }
+            if end > start {
+                words.push(Spanned::new(word, span_of(start, end)));
+            }
+        }
+        Ok(words)
+    }
+}
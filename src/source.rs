@@ -0,0 +1,203 @@
+use crate::{FileId, Span};
+use bstr::ByteSlice;
+use eyre::{Context, Result};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+pub(crate) struct Source {
+    pub content: Arc<[u8]>,
+    /// Computed on first use.
+    line_starts: OnceLock<Vec<usize>>,
+}
+
+impl Source {
+    fn new(content: Arc<[u8]>) -> Self {
+        Self {
+            content,
+            line_starts: OnceLock::new(),
+        }
+    }
+
+    /// The byte offsets at which lines start. A trailing newline starts an (empty) last line.
+    pub fn line_starts(&self) -> &[usize] {
+        self.line_starts.get_or_init(|| {
+            std::iter::once(0)
+                .chain(self.content.find_iter("\n").map(|i| i + 1))
+                .collect()
+        })
+    }
+}
+
+type Sources = RwLock<HashMap<PathBuf, Arc<Source>>>;
+
+fn sources() -> &'static Sources {
+    static SOURCES: OnceLock<Sources> = OnceLock::new();
+    SOURCES.get_or_init(Default::default)
+}
+
+/// Files read from disk, so that rendering many diagnostics doesn't read the same file
+/// over and over.
+fn cache() -> &'static Sources {
+    static CACHE: OnceLock<Sources> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Serves file contents from somewhere other than the file system, e.g. a virtual file
+/// system or an archive, without registering every file up front.
+pub trait SourceProvider: Send + Sync {
+    /// The contents of `path`, or `None` to read the file from disk instead.
+    fn load(&self, path: &Path) -> Option<Arc<str>>;
+}
+
+type Provider = RwLock<Option<Box<dyn SourceProvider>>>;
+
+fn provider() -> &'static Provider {
+    static PROVIDER: OnceLock<Provider> = OnceLock::new();
+    PROVIDER.get_or_init(Default::default)
+}
+
+/// Ask `provider` for the contents of files before reading them from disk. Registered
+/// sources still take precedence. Clears the cache of files read so far.
+pub fn set_source_provider(provider: impl SourceProvider + 'static) {
+    *self::provider()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Box::new(provider));
+    clear_source_cache();
+}
+
+/// Make `content` the source of `path` for everything that needs to look at the
+/// source of a span (line/column lookup, anchored spans, ...), instead of reading the file.
+/// This also works for paths that don't exist on disk, like `<stdin>`.
+pub fn register_source(path: impl Into<PathBuf>, content: impl Into<Arc<[u8]>>) {
+    sources()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(path.into(), Arc::new(Source::new(content.into())));
+}
+
+/// Forget about a source registered with `register_source`, so the file is read again.
+pub fn unregister_source(path: &Path) {
+    sources()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(path);
+}
+
+/// Forget the cached contents of a file read from disk, so the next lookup reads it again.
+/// Needed if the file was changed while the process is running.
+pub fn invalidate_source(path: &Path) {
+    cache()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(path);
+}
+
+/// Forget the cached contents of all files read from disk.
+pub fn clear_source_cache() {
+    cache()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+thread_local! {
+    /// The snapshots of the diagnostic currently being rendered, see `with_snapshots`.
+    static SNAPSHOTS: RefCell<Vec<(FileId, Arc<Source>)>> = const { RefCell::new(vec![]) };
+}
+
+/// Run `f` with the snapshotted file contents taking precedence over everything else.
+pub(crate) fn with_snapshots<R>(snapshots: &[(FileId, Arc<[u8]>)], f: impl FnOnce() -> R) -> R {
+    if snapshots.is_empty() {
+        return f();
+    }
+    struct Restore(Vec<(FileId, Arc<Source>)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SNAPSHOTS.with(|snapshots| *snapshots.borrow_mut() = std::mem::take(&mut self.0));
+        }
+    }
+    let snapshots = snapshots
+        .iter()
+        .map(|(path, content)| (*path, Arc::new(Source::new(content.clone()))));
+    let _restore = Restore(SNAPSHOTS.with(|current| {
+        let previous = current.borrow().clone();
+        current.borrow_mut().extend(snapshots);
+        previous
+    }));
+    f()
+}
+
+/// Read the contents of the file a span points into.
+pub(crate) fn read(path: &Path) -> Result<Arc<[u8]>> {
+    Ok(load(path)?.content.clone())
+}
+
+/// The registered source or the cached file contents, reading the file (or asking the
+/// `SourceProvider`) on first use.
+pub(crate) fn load(path: &Path) -> Result<Arc<Source>> {
+    let snapshot = SNAPSHOTS.with(|snapshots| {
+        let snapshots = snapshots.borrow();
+        let (_, source) = snapshots.iter().rev().find(|(file, _)| **file == *path)?;
+        Some(source.clone())
+    });
+    if let Some(source) = snapshot {
+        return Ok(source);
+    }
+    if let Some(source) = get(sources(), path).or_else(|| get(cache(), path)) {
+        return Ok(source);
+    }
+    reload(path)
+}
+
+/// Like `load`, but reads the file again even if it is cached.
+pub(crate) fn reload(path: &Path) -> Result<Arc<Source>> {
+    if let Some(source) = get(sources(), path) {
+        return Ok(source);
+    }
+    let provided = provider()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .and_then(|provider| provider.load(path));
+    let content: Arc<[u8]> = match provided {
+        Some(content) => content.into(),
+        None => std::fs::read(path)
+            .with_context(|| path.display().to_string())?
+            .into(),
+    };
+    let source = Arc::new(Source::new(content));
+    cache()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(path.to_path_buf(), source.clone());
+    Ok(source)
+}
+
+fn get(sources: &Sources, path: &Path) -> Option<Arc<Source>> {
+    sources
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(path)
+        .cloned()
+}
+
+impl Span {
+    /// A span covering the entire file, for errors that apply to the file as a whole.
+    pub fn whole_file(path: impl Into<PathBuf>) -> Result<Span> {
+        let file = path.into();
+        let len = read(&file)?.len();
+        Ok(Span {
+            file: file.into(),
+            bytes: 0..len,
+        })
+    }
+
+    /// An empty span at the end of the file, e.g. for "unexpected end of input".
+    pub fn eof(path: impl Into<PathBuf>) -> Result<Span> {
+        Ok(Self::whole_file(path)?.shrink_to_end())
+    }
+}
@@ -1,8 +1,17 @@
+use crate::FileId;
 use bstr::{ByteSlice, Utf8Error};
-use color_eyre::{eyre::Context, Report, Result};
-use std::{fmt::Display, ops::Range, path::PathBuf, str::FromStr};
+use eyre::{eyre, Context, Report, Result};
+use std::{
+    borrow::Borrow,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Spanned<T> {
     pub span: Span,
     pub content: T,
@@ -24,9 +33,57 @@ impl<T: std::fmt::Debug> std::fmt::Debug for Spanned<T> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+impl AsRef<str> for Spanned<String> {
+    fn as_ref(&self) -> &str {
+        &self.content
+    }
+}
+
+impl AsRef<str> for Spanned<&str> {
+    fn as_ref(&self) -> &str {
+        self.content
+    }
+}
+
+/// Allows looking up `Spanned<String>` keys in maps and sets by `&str`.
+impl Borrow<str> for Spanned<String> {
+    fn borrow(&self) -> &str {
+        &self.content
+    }
+}
+
+impl Borrow<str> for Spanned<&str> {
+    fn borrow(&self) -> &str {
+        self.content
+    }
+}
+
+impl From<Spanned<&str>> for Spanned<String> {
+    fn from(s: Spanned<&str>) -> Self {
+        s.map(String::from)
+    }
+}
+
+/// Only hashes the content, to be consistent with `PartialEq` and `Borrow`.
+impl<T: Hash> Hash for Spanned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.content.hash(state)
+    }
+}
+
+/// Only shows the content, use `Debug` or the `span` field to get at the span.
+impl<T: Display> Display for Spanned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.content.fmt(f)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
-    pub file: PathBuf,
+    /// Interned, so that cloning a span (which most string operations do) is just a copy.
+    #[cfg_attr(feature = "serde", serde(with = "crate::intern"))]
+    pub file: FileId,
     pub bytes: Range<usize>,
 }
 
@@ -38,7 +95,7 @@ impl std::fmt::Debug for Span {
 impl Default for Span {
     fn default() -> Self {
         Self {
-            file: PathBuf::new(),
+            file: FileId::empty(),
             bytes: usize::MAX..usize::MAX,
         }
     }
@@ -46,7 +103,7 @@ impl Default for Span {
 
 impl Span {
     pub fn is_dummy(&self) -> bool {
-        self == &Self::default()
+        self.bytes == (usize::MAX..usize::MAX) && self.file.as_os_str().is_empty()
     }
     #[track_caller]
     pub fn dec_col_end(mut self, amount: usize) -> Self {
@@ -69,6 +126,74 @@ impl Span {
         self.bytes.end = new;
         self
     }
+
+    /// Like `dec_col_end`, but returns an error instead of panicking.
+    pub fn try_dec_col_end(mut self, amount: usize) -> Result<Self> {
+        match self.bytes.end.checked_sub(amount) {
+            Some(new) if self.bytes.start <= new => {
+                self.bytes.end = new;
+                Ok(self)
+            }
+            _ => Err(eyre!("{self}: cannot move end back by {amount}")),
+        }
+    }
+
+    /// Like `inc_col_start`, but returns an error instead of panicking.
+    pub fn try_inc_col_start(mut self, amount: usize) -> Result<Self> {
+        match self.bytes.start.checked_add(amount) {
+            Some(new) if new <= self.bytes.end => {
+                self.bytes.start = new;
+                Ok(self)
+            }
+            _ => Err(eyre!("{self}: cannot move start forward by {amount}")),
+        }
+    }
+
+    /// Like `set_col_end_relative_to_start`, but returns an error instead of panicking.
+    pub fn try_set_col_end_relative_to_start(mut self, amount: usize) -> Result<Self> {
+        match self.bytes.start.checked_add(amount) {
+            Some(new) if new <= self.bytes.end => {
+                self.bytes.end = new;
+                Ok(self)
+            }
+            _ => Err(eyre!("{self}: cannot set length to {amount}")),
+        }
+    }
+
+    /// Like `dec_col_end`, but stops at the start of the span.
+    pub fn saturating_dec_col_end(mut self, amount: usize) -> Self {
+        self.bytes.end = self.bytes.end.saturating_sub(amount).max(self.bytes.start);
+        self
+    }
+
+    /// Like `inc_col_start`, but stops at the end of the span.
+    pub fn saturating_inc_col_start(mut self, amount: usize) -> Self {
+        self.bytes.start = self.bytes.start.saturating_add(amount).min(self.bytes.end);
+        self
+    }
+
+    /// Like `set_col_end_relative_to_start`, but keeps the end if the span is too short.
+    pub fn saturating_set_col_end_relative_to_start(mut self, amount: usize) -> Self {
+        self.bytes.end = self.bytes.start.saturating_add(amount).min(self.bytes.end);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Split into the spans before and after `offset`, which is relative to the start.
+    #[track_caller]
+    pub fn split_at(&self, offset: usize) -> (Span, Span) {
+        let first = self.clone().set_col_end_relative_to_start(offset);
+        let second = self.clone().inc_col_start(offset);
+        (first, second)
+    }
+
     pub fn shrink_to_end(mut self) -> Span {
         self.bytes.start = self.bytes.end;
         self
@@ -78,6 +203,26 @@ impl Span {
         self.bytes.end = self.bytes.start;
         self
     }
+
+    /// The smallest span covering both spans. Dummy spans are ignored, and if the spans
+    /// are in different files, `self` is returned unchanged.
+    pub fn join(mut self, other: &Span) -> Span {
+        if self.is_dummy() {
+            return other.clone();
+        }
+        if other.is_dummy() || self.file != other.file {
+            return self;
+        }
+        self.bytes.start = self.bytes.start.min(other.bytes.start);
+        self.bytes.end = self.bytes.end.max(other.bytes.end);
+        self
+    }
+}
+
+impl From<&Span> for Range<usize> {
+    fn from(span: &Span) -> Self {
+        span.bytes.clone()
+    }
 }
 
 impl Display for Span {
@@ -94,9 +239,9 @@ impl Display for Span {
 impl Spanned<&str> {
     pub fn split_once(&self, delimiter: &str) -> Option<(Self, Self)> {
         let (a, b) = self.content.split_once(delimiter)?;
-        let span = self.span.clone().dec_col_end(b.len());
+        let span = self.span.clone().set_col_end_relative_to_start(a.len());
         let a = Spanned { span, content: a };
-        let span = self.span.clone().inc_col_start(a.len() + 1);
+        let span = self.span.clone().inc_col_start(a.len() + delimiter.len());
         let b = Spanned { span, content: b };
         Some((a, b))
     }
@@ -106,6 +251,26 @@ impl Spanned<&str> {
         Some(self.split_at(pos))
     }
 
+    /// Like `take_while`, but if all chars match, everything is taken and the rest is empty.
+    pub fn take_while_or_all(&self, predicate: impl Fn(char) -> bool) -> (Self, Self) {
+        let pos = self
+            .content
+            .find(|c| !predicate(c))
+            .unwrap_or(self.content.len());
+        self.split_at(pos)
+    }
+
+    /// Everything after the leading chars that match `predicate`.
+    pub fn skip_while(&self, predicate: impl Fn(char) -> bool) -> Self {
+        self.take_while_or_all(predicate).1
+    }
+
+    /// Split before the first occurrence of `pat`, or take everything if there is none.
+    pub fn take_until(&self, pat: &str) -> (Self, Self) {
+        let pos = self.content.find(pat).unwrap_or(self.content.len());
+        self.split_at(pos)
+    }
+
     pub fn split_at(&self, pos: usize) -> (Self, Self) {
         let (a, b) = self.content.split_at(pos);
         let n = a.len();
@@ -154,6 +319,25 @@ impl Spanned<&str> {
         self.content.starts_with(pat)
     }
 
+    pub fn ends_with(&self, pat: &str) -> bool {
+        self.content.ends_with(pat)
+    }
+
+    pub fn starts_with_ignore_ascii_case(&self, prefix: &str) -> bool {
+        self.content
+            .as_bytes()
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix.as_bytes()))
+    }
+
+    /// Like `strip_prefix`, but ignores ASCII case differences.
+    pub fn strip_prefix_ignore_ascii_case(&self, prefix: &str) -> Option<Self> {
+        if !self.starts_with_ignore_ascii_case(prefix) {
+            return None;
+        }
+        Some(self.split_at(prefix.len()).1)
+    }
+
     pub fn parse<T: FromStr>(self) -> Result<Spanned<T>>
     where
         T::Err: Into<Report>,
@@ -169,6 +353,24 @@ impl Spanned<&str> {
         })
     }
 
+    /// Like `lines`, but keeps the `str` type and the lifetime of the content.
+    pub(crate) fn str_lines(self) -> impl Iterator<Item = Self> {
+        self.content.lines().map(move |line| {
+            // SAFETY: `line` is a substr of `content`, so the `offset_from` requirements are
+            // trivially satisfied.
+            let amount = unsafe { line.as_ptr().offset_from(self.content.as_ptr()) };
+            let span = self
+                .span
+                .clone()
+                .inc_col_start(amount.try_into().unwrap())
+                .set_col_end_relative_to_start(line.len());
+            Spanned {
+                content: line,
+                span,
+            }
+        })
+    }
+
     pub fn chars(&self) -> impl Iterator<Item = Spanned<char>> + '_ {
         self.content.chars().enumerate().map(move |(i, c)| {
             Spanned::new(c, self.span.clone().inc_col_start(i).shrink_to_start())
@@ -244,7 +446,87 @@ impl Spanned<Vec<u8>> {
         let path_str = path.display().to_string();
         let content = std::fs::read(&path).with_context(|| path_str)?;
         let span = Span {
-            file: path,
+            file: path.into(),
+            bytes: 0..content.len(),
+        };
+        Ok(Self { span, content })
+    }
+
+    /// Read all of stdin. The resulting span uses `<stdin>` as its file, which is
+    /// registered as a source so line and column lookups work.
+    pub fn read_from_stdin() -> Result<Self> {
+        let mut content = vec![];
+        std::io::Read::read_to_end(&mut std::io::stdin().lock(), &mut content)
+            .context("<stdin>")?;
+        crate::register_source("<stdin>", &content[..]);
+        let span = Span {
+            file: Path::new("<stdin>").into(),
+            bytes: 0..content.len(),
+        };
+        Ok(Self { span, content })
+    }
+
+    /// Lazily read a file line by line. Each line has the line terminator removed, just
+    /// like `lines`, but its span is still relative to the start of the file.
+    pub fn read_lines_from_file(
+        path: impl Into<PathBuf>,
+    ) -> Result<impl Iterator<Item = Result<Self>>> {
+        let path = FileId::from(path.into());
+        let path_str = path.display().to_string();
+        let file = std::fs::File::open(path).with_context(|| path_str.clone())?;
+        let mut reader = std::io::BufReader::new(file);
+        let mut offset = 0;
+        Ok(std::iter::from_fn(move || {
+            let mut content = vec![];
+            let n = match std::io::BufRead::read_until(&mut reader, b'\n', &mut content) {
+                Ok(0) => return None,
+                Ok(n) => n,
+                Err(err) => return Some(Err(err).with_context(|| path_str.clone())),
+            };
+            if content.last() == Some(&b'\n') {
+                content.pop();
+                if content.last() == Some(&b'\r') {
+                    content.pop();
+                }
+            }
+            let span = Span {
+                file: path,
+                bytes: offset..offset + content.len(),
+            };
+            offset += n;
+            Some(Ok(Self { span, content }))
+        }))
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn read_from_file_async(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let path_str = path.display().to_string();
+        let content = tokio::fs::read(&path).await.with_context(|| path_str)?;
+        let span = Span {
+            file: path.into(),
+            bytes: 0..content.len(),
+        };
+        Ok(Self { span, content })
+    }
+}
+
+#[cfg(feature = "memmap")]
+impl Spanned<memmap2::Mmap> {
+    /// Map the file into memory instead of reading it. Use `as_ref` or `lines`
+    /// to get `Spanned<&[u8]>` views into the mapping.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the mapping is alive,
+    /// see [`memmap2::Mmap::map`].
+    pub unsafe fn map_file(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let path_str = path.display().to_string();
+        let file = std::fs::File::open(&path).with_context(|| path_str.clone())?;
+        let content = memmap2::Mmap::map(&file).with_context(|| path_str)?;
+        let span = Span {
+            file: path.into(),
             bytes: 0..content.len(),
         };
         Ok(Self { span, content })
@@ -269,3 +551,31 @@ impl<T: AsRef<[u8]>> Spanned<T> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn spanned(s: &str) -> Spanned<&str> {
+        let span = Span {
+            file: Path::new("test").into(),
+            bytes: 10..10 + s.len(),
+        };
+        Spanned::new(s, span)
+    }
+
+    #[test]
+    fn split_once_multi_byte_delimiter() {
+        let (key, value) = spanned("key => value").split_once(" => ").unwrap();
+        assert_eq!((key.content, key.span.bytes), ("key", 10..13));
+        assert_eq!((value.content, value.span.bytes), ("value", 17..22));
+    }
+
+    #[test]
+    fn split_once_non_ascii_delimiter() {
+        let (a, b) = spanned("a→b").split_once("→").unwrap();
+        assert_eq!((a.content, a.span.bytes), ("a", 10..11));
+        assert_eq!((b.content, b.span.bytes), ("b", 14..15));
+    }
+}
@@ -0,0 +1,55 @@
+//! Typo hints for identifiers, keywords and the like.
+
+use crate::{Diagnostic, Spanned};
+
+/// The candidate closest to `input`, if any is close enough to plausibly be a typo.
+/// Ties go to the earlier candidate.
+pub fn closest<'c>(
+    input: &Spanned<&str>,
+    candidates: impl IntoIterator<Item = &'c str>,
+) -> Option<&'c str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (distance(input.content, candidate), candidate))
+        .filter(|&(distance, candidate)| distance <= (candidate.chars().count() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of single char insertions, deletions, substitutions and swaps of adjacent
+/// chars to get from `a` to `b` (optimal string alignment distance).
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // The rows of the distance matrix for the previous two and the current char of `a`.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+impl Diagnostic {
+    /// Add a "did you mean" help if one of the candidates is close to `input`.
+    pub fn help_did_you_mean<'c>(
+        self,
+        input: &Spanned<&str>,
+        candidates: impl IntoIterator<Item = &'c str>,
+    ) -> Self {
+        match closest(input, candidates) {
+            Some(candidate) => self.help(format!("did you mean `{candidate}`?")),
+            None => self,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers for golden tests of rendered diagnostics.
+
+use crate::{Diagnostic, Renderer};
+use std::fmt::Write;
+
+/// Render without colors, with `/` as path separator and paths relative to the
+/// current directory, so the output is the same on every machine.
+pub fn render(diagnostic: &Diagnostic) -> String {
+    let mut renderer = Renderer::deterministic();
+    if let Ok(dir) = std::env::current_dir() {
+        renderer = renderer.strip_path_prefix(dir);
+    }
+    renderer.render(diagnostic)
+}
+
+/// Panics with a line diff if `render(diagnostic)` differs from `expected`.
+/// Trailing whitespace at the end of either is ignored.
+#[track_caller]
+pub fn assert_rendered(diagnostic: &Diagnostic, expected: &str) {
+    assert_text_eq(&render(diagnostic), expected);
+}
+
+/// Panics with a line diff if the texts differ, ignoring trailing whitespace at the end.
+#[track_caller]
+pub fn assert_text_eq(actual: &str, expected: &str) {
+    let (actual, expected) = (actual.trim_end(), expected.trim_end());
+    if actual != expected {
+        panic!(
+            "rendered output differs (-expected +actual):\n{}",
+            diff(expected, actual)
+        );
+    }
+}
+
+/// A minimal line based diff, with unchanged lines prefixed by a space.
+fn diff(old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+    // Length of the longest common subsequence of `old[i..]` and `new[j..]`.
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            writeln!(out, " {}", old[i]).unwrap();
+            (i, j) = (i + 1, j + 1);
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(out, "-{}", old[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(out, "+{}", new[j]).unwrap();
+            j += 1;
+        }
+    }
+    out
+}
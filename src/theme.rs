@@ -0,0 +1,103 @@
+use crate::Level;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bold: false,
+            underline: false,
+        }
+    }
+
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// The ANSI escape sequence selecting this style, empty for the default style.
+    pub(crate) fn ansi(self) -> String {
+        let mut codes = vec![];
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.underline {
+            codes.push("4".to_string());
+        }
+        if let Some(fg) = self.fg {
+            codes.push((30 + fg as u8).to_string());
+        }
+        if codes.is_empty() {
+            return String::new();
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// The styles used by a colored `Renderer`. The default mimics rustc.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Theme {
+    pub error: Style,
+    pub warning: Style,
+    pub info: Style,
+    pub note: Style,
+    pub help: Style,
+    /// The message after the level.
+    pub message: Style,
+    /// The `-->` before a file path.
+    pub arrow: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            error: Style::new().fg(Color::Red).bold(),
+            warning: Style::new().fg(Color::Yellow).bold(),
+            info: Style::new().bold(),
+            note: Style::new().fg(Color::Green).bold(),
+            help: Style::new().fg(Color::Cyan).bold(),
+            message: Style::new().bold(),
+            arrow: Style::new().fg(Color::Blue).bold(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn level(&self, level: Level) -> Style {
+        match level {
+            Level::Error => self.error,
+            Level::Warning => self.warning,
+            Level::Info => self.info,
+            Level::Note => self.note,
+            Level::Help => self.help,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Interop with the span types of the `toml` crate family.
+
+use crate::{Span, Spanned};
+
+#[cfg(feature = "serde_spanned")]
+impl<T> Spanned<T> {
+    /// Convert a `serde_spanned::Spanned`, e.g. from a `toml` deserialization. Its byte range
+    /// is relative to the deserialized document, whose span is given as `document`, and is
+    /// clamped to it.
+    /// For documents that aren't files on disk, use `register_source` to make them renderable.
+    pub fn from_serde_spanned(spanned: serde_spanned::Spanned<T>, document: &Span) -> Self {
+        let range = spanned.span();
+        let span = document
+            .clone()
+            .saturating_inc_col_start(range.start)
+            .saturating_set_col_end_relative_to_start(range.len());
+        Spanned::new(spanned.into_inner(), span)
+    }
+}
+
+/// Drops the file, keeping only the byte range.
+#[cfg(feature = "serde_spanned")]
+impl<T> From<Spanned<T>> for serde_spanned::Spanned<T> {
+    fn from(spanned: Spanned<T>) -> Self {
+        serde_spanned::Spanned::new(spanned.span.bytes, spanned.content)
+    }
+}
+
+#[cfg(feature = "toml_edit")]
+impl Spanned<String> {
+    /// The span of the key at `path` in `document`, which must have been parsed from `self`.
+    /// Path elements are keys, or indices for arrays. E.g. `["dependencies", "serde"]`.
+    pub fn toml_key_span<S>(
+        &self,
+        document: &toml_edit::Document<S>,
+        path: &[&str],
+    ) -> Option<Span> {
+        let (key, _) = toml_lookup(document, path)?;
+        self.toml_span(key?.span()?)
+    }
+
+    /// The span of the value at `path` in `document`, which must have been parsed from `self`.
+    pub fn toml_value_span<S>(
+        &self,
+        document: &toml_edit::Document<S>,
+        path: &[&str],
+    ) -> Option<Span> {
+        let (_, item) = toml_lookup(document, path)?;
+        self.toml_span(item.span()?)
+    }
+
+    fn toml_span(&self, range: std::ops::Range<usize>) -> Option<Span> {
+        self.span
+            .clone()
+            .try_inc_col_start(range.start)
+            .and_then(|span| span.try_set_col_end_relative_to_start(range.len()))
+            .ok()
+    }
+}
+
+#[cfg(feature = "toml_edit")]
+fn toml_lookup<'a, S>(
+    document: &'a toml_edit::Document<S>,
+    path: &[&str],
+) -> Option<(Option<&'a toml_edit::Key>, &'a toml_edit::Item)> {
+    let mut key = None;
+    let mut item = document.as_item();
+    for &segment in path {
+        if let Some(table) = item.as_table_like() {
+            let (k, i) = table.get_key_value(segment)?;
+            key = Some(k);
+            item = i;
+        } else {
+            key = None;
+            item = item.get(segment.parse::<usize>().ok()?)?;
+        }
+    }
+    Some((key, item))
+}
@@ -0,0 +1,22 @@
+use crate::{source, Column, Diagnostic, Level};
+
+impl Diagnostic {
+    /// Emit as a `tracing` event with `file`, `line`, `column` and `message` fields.
+    /// Errors and warnings map to the same `tracing` levels, everything else to `INFO`.
+    pub fn trace(&self) {
+        source::with_snapshots(&self.snapshots, || {
+            let file = (!self.span.is_dummy()).then(|| self.span.file.display().to_string());
+            let position = self.span.line_col(Column::Char).ok();
+            let line = position.map(|position| position.line);
+            let column = position.map(|position| position.column);
+            let message = &self.message;
+            match self.level {
+                Level::Error => tracing::error!(file, line, column, message),
+                Level::Warning => tracing::warn!(file, line, column, message),
+                Level::Info | Level::Note | Level::Help => {
+                    tracing::info!(file, line, column, message, level = %self.level)
+                }
+            }
+        })
+    }
+}
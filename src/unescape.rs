@@ -0,0 +1,98 @@
+use crate::{err, Mapped, Segment, Spanned};
+use eyre::Result;
+
+impl Spanned<&str> {
+    /// Resolve the escape sequences `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\'` and `\u{...}`.
+    /// `Mapped::span_of` maps every unescaped character back to its escape sequence.
+    pub fn unescape(&self) -> Result<Mapped> {
+        let mut content = String::new();
+        let mut segments = vec![];
+        let mut rest = self.clone();
+        while let Some(start) = rest.find('\\') {
+            let (literal, escape) = rest.split_at(start);
+            if !literal.is_empty() {
+                segments.push(Segment {
+                    output: content.len()..content.len() + literal.len(),
+                    span: literal.span,
+                    literal: true,
+                });
+                content.push_str(literal.content);
+            }
+            let Some(c) = escape[1..].chars().next() else {
+                return Err(err!(escape.span, "unterminated escape sequence"));
+            };
+            let (escape, after) = if c == 'u' {
+                let Some(end) = escape.find('}').filter(|_| escape[2..].starts_with('{')) else {
+                    return Err(err!(
+                        escape.span.clone().set_col_end_relative_to_start(2),
+                        "expected `\\u{{...}}`"
+                    ));
+                };
+                escape.split_at(end + 1)
+            } else {
+                escape.split_at(1 + c.len_utf8())
+            };
+            let unescaped = match c {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '0' => '\0',
+                '\\' | '"' | '\'' => c,
+                'u' => Some(&escape[3..escape.len() - 1])
+                    // `from_str_radix` alone would also accept a leading `+`.
+                    .filter(|digits| digits.chars().all(|c| c.is_ascii_hexdigit()))
+                    .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| err!(escape.span.clone(), "invalid unicode escape"))?,
+                _ => return Err(err!(escape.span, "unknown escape sequence `\\{c}`")),
+            };
+            segments.push(Segment {
+                output: content.len()..content.len() + unescaped.len_utf8(),
+                span: escape.span,
+                literal: false,
+            });
+            content.push(unescaped);
+            rest = after;
+        }
+        if !rest.is_empty() {
+            segments.push(Segment {
+                output: content.len()..content.len() + rest.len(),
+                span: rest.span,
+                literal: true,
+            });
+            content.push_str(rest.content);
+        }
+        Ok(Mapped {
+            content: Spanned::new(content, self.span.clone()),
+            segments,
+        })
+    }
+
+    /// Parse a `"..."` or `'...'` string at the start, returning its unescaped content,
+    /// whose span excludes the quotes, and the rest of the input after the closing quote.
+    pub fn parse_quoted(&self) -> Result<(Mapped, Self)> {
+        let Some(quote) = self
+            .content
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+        else {
+            return Err(err!(
+                self.span.clone().shrink_to_start(),
+                "expected a quoted string"
+            ));
+        };
+        let (open, rest) = self.split_at(1);
+        let mut escaped = false;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            let found = !escaped && c == quote;
+            escaped = !escaped && c == '\\';
+            found.then_some(i)
+        });
+        let Some(end) = end else {
+            return Err(err!(open.span, "unterminated string"));
+        };
+        let (inner, after) = rest.split_at(end);
+        Ok((inner.unescape()?, after.split_at(1).1))
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{err, Spanned};
+use eyre::Result;
+
+/// A semantic version where every component points at its text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemVer<'a> {
+    pub major: Spanned<u64>,
+    pub minor: Spanned<u64>,
+    pub patch: Spanned<u64>,
+    /// The part after the `-`, e.g. `alpha.1`.
+    pub pre: Option<Spanned<&'a str>>,
+    /// The part after the `+`.
+    pub build: Option<Spanned<&'a str>>,
+}
+
+impl SemVer<'_> {
+    pub fn version(&self) -> semver::Version {
+        semver::Version {
+            major: self.major.content,
+            minor: self.minor.content,
+            patch: self.patch.content,
+            pre: self.pre.as_ref().map_or(semver::Prerelease::EMPTY, |pre| {
+                semver::Prerelease::new(pre.content).unwrap()
+            }),
+            build: self
+                .build
+                .as_ref()
+                .map_or(semver::BuildMetadata::EMPTY, |build| {
+                    semver::BuildMetadata::new(build.content).unwrap()
+                }),
+        }
+    }
+}
+
+fn number(part: Spanned<&str>, name: &str) -> Result<Spanned<u64>> {
+    if part.is_empty() {
+        return Err(err!(part.span, "missing {name} version"));
+    }
+    if part.len() > 1 && part.starts_with("0") {
+        return Err(err!(
+            part.span,
+            "{name} version must not have leading zeros"
+        ));
+    }
+    if !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(err!(part.span, "{name} version must be a number"));
+    }
+    part.parse()
+}
+
+impl<'a> Spanned<&'a str> {
+    /// Parse `major.minor.patch[-pre][+build]`. Errors point at the malformed component.
+    pub fn parse_semver(&self) -> Result<SemVer<'a>> {
+        let (rest, build) = match self.split_once("+") {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (self.clone(), None),
+        };
+        let (core, pre) = match rest.split_once("-") {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+        let (major, rest) = core
+            .split_once(".")
+            .ok_or_else(|| err!(core.span.clone(), "expected `major.minor.patch`"))?;
+        let (minor, patch) = rest
+            .split_once(".")
+            .ok_or_else(|| err!(rest.span.clone(), "expected `minor.patch`"))?;
+        let major = number(major, "major")?;
+        let minor = number(minor, "minor")?;
+        let patch = number(patch, "patch")?;
+        if let Some(pre) = &pre {
+            semver::Prerelease::new(pre.content).map_err(|e| err!(pre.span.clone(), "{e}"))?;
+        }
+        if let Some(build) = &build {
+            semver::BuildMetadata::new(build.content)
+                .map_err(|e| err!(build.span.clone(), "{e}"))?;
+        }
+        Ok(SemVer {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}